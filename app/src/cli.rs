@@ -0,0 +1,85 @@
+use config::Override;
+
+pub const USAGE: &str = "Usage: tvmenu [--set [Section.]key=value]...
+       tvmenu config schema [--json]
+       tvmenu history [reset [TITLE]]
+
+Commands:
+  config schema              Print all config keys as markdown, or as JSON with --json
+  history                    Print the launch history, most recently launched first
+  history reset [TITLE]      Remove TITLE from the launch history, or all entries
+
+Options:
+  --set [Section.]key=value  Override a config value, may be given multiple times
+  -h, --help                 Print this help";
+
+/// What to do after parsing arguments
+#[derive(Debug, Default, PartialEq, Eq)]
+pub enum Command {
+    /// Start the menu
+    #[default]
+    Run,
+    /// Print the config schema
+    ConfigSchema { json: bool },
+    /// Print the launch history
+    History,
+    /// Remove an entry or all entries from the launch history
+    HistoryReset { title: Option<String> },
+}
+
+/// Parsed command line arguments
+#[derive(Default)]
+pub struct Args {
+    pub command: Command,
+    /// Config values set with `--set`
+    pub overrides: Vec<Override>,
+    /// Print usage and exit
+    pub help: bool,
+}
+
+/// Parse command line arguments (without the program name)
+/// # Errors
+/// Returns an error message if an argument is unknown or malformed
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter().peekable();
+    if args.peek().is_some_and(|arg| arg == "config") {
+        args.next();
+        match args.next().as_deref() {
+            Some("schema") => parsed.command = Command::ConfigSchema { json: false },
+            Some(sub) => return Err(format!("Unknown config command {sub}")),
+            None => return Err("Missing config command".to_owned()),
+        }
+    } else if args.peek().is_some_and(|arg| arg == "history") {
+        args.next();
+        parsed.command = Command::History;
+        if args.next_if(|arg| arg == "reset").is_some() {
+            let title = args.next_if(|arg| !arg.starts_with('-'));
+            parsed.command = Command::HistoryReset { title };
+        }
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => parsed.help = true,
+            "--json" => match &mut parsed.command {
+                Command::ConfigSchema { json } => *json = true,
+                _ => return Err("--json is only used by config schema".to_owned()),
+            },
+            "--set" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "Missing value for --set".to_owned())?;
+                parsed.overrides.push(parse_override(&value)?);
+            }
+            _ => match arg.strip_prefix("--set=") {
+                Some(value) => parsed.overrides.push(parse_override(value)?),
+                None => return Err(format!("Unknown argument {arg}")),
+            },
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_override(value: &str) -> Result<Override, String> {
+    Override::from_arg(value).map_err(|err| err.to_string())
+}
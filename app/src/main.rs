@@ -1,8 +1,96 @@
-use gui::run_app;
+use config::AppConfig;
+use entries::{History, unix_time};
+use gui::{paths::history_path, run_app};
+use std::{env::args, path::PathBuf, process::exit};
+
+mod cli;
+use cli::{Command, USAGE, parse_args};
 
 fn main() {
-    match run_app() {
-        Ok(()) => (),
-        Err(err) => eprintln!("App exited with error:\n{err}"),
+    let args = match parse_args(args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            exit(2)
+        }
+    };
+    if args.help {
+        println!("{USAGE}");
+        return;
+    }
+    match args.command {
+        Command::ConfigSchema { json } => {
+            let schema = AppConfig::schema();
+            if json {
+                println!("{}", schema.to_json());
+            } else {
+                print!("{}", schema.to_markdown());
+            }
+        }
+        Command::History => {
+            if let Err(err) = print_history() {
+                eprintln!("{err}");
+                exit(1)
+            }
+        }
+        Command::HistoryReset { title } => {
+            if let Err(err) = reset_history(title.as_deref()) {
+                eprintln!("{err}");
+                exit(1)
+            }
+        }
+        Command::Run => match run_app(args.overrides) {
+            Ok(()) => (),
+            Err(err) => eprintln!("App exited with error:\n{err}"),
+        },
+    }
+}
+
+fn history_file() -> Result<PathBuf, String> {
+    history_path().ok_or_else(|| "Could not get the state directory".to_owned())
+}
+
+/// Print launch count, time since the last launch, title and command of each launched entry
+fn print_history() -> Result<(), String> {
+    let history = History::load(&history_file()?).map_err(|err| err.to_string())?;
+    if history.is_empty() {
+        println!("No entries launched yet");
+        return Ok(());
     }
+    let now = unix_time();
+    println!("{:>8}  {:>14}  Title", "Launches", "Last launch");
+    for (title, command, launches) in history.iter() {
+        let age = format_age(now.saturating_sub(launches.last));
+        match command {
+            Some(command) => println!("{:>8}  {age:>14}  {title} ({command})", launches.count),
+            None => println!("{:>8}  {age:>14}  {title}", launches.count),
+        }
+    }
+    Ok(())
+}
+
+/// Remove `title` from the history, or the whole history if no title is given
+fn reset_history(title: Option<&str>) -> Result<(), String> {
+    let path = history_file()?;
+    let Some(title) = title else {
+        return History::reset(&path).map_err(|err| err.to_string());
+    };
+    let mut history = History::load(&path).map_err(|err| err.to_string())?;
+    if !history.remove(title) {
+        return Err(format!("{title} is not in the launch history"));
+    }
+    history.save(&path).map_err(|err| err.to_string())
+}
+
+/// Format a duration in seconds in the largest fitting unit, e.g. `3 hours ago`
+fn format_age(secs: u64) -> String {
+    let units = [(86400, "day"), (3600, "hour"), (60, "minute")];
+    units.iter().find(|(len, _)| secs >= *len).map_or_else(
+        || "just now".to_owned(),
+        |(len, unit)| {
+            let count = secs / len;
+            let plural = if count == 1 { "" } else { "s" };
+            format!("{count} {unit}{plural} ago")
+        },
+    )
 }
@@ -1,6 +1,7 @@
 use crate::Error;
+use parser::{FromConfigValue, ToConfigValue};
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Color {
     pub red: u8,
     pub green: u8,
@@ -56,6 +57,31 @@ impl Color {
     }
 }
 
+impl FromConfigValue for Color {
+    fn expected() -> String {
+        "color (#rrggbb, #rrggbbaa, rgb(r,g,b) or rgba(r,g,b,a))".to_owned()
+    }
+
+    fn from_config_value(value: &str) -> Result<Self, parser::Error> {
+        parse_color(value).map_err(|_| parser::Error::invalid_type(value, &Self::expected()))
+    }
+}
+
+impl ToConfigValue for Color {
+    /// Hex notation, the alpha channel is left out for opaque colors
+    fn to_config_value(&self) -> String {
+        let rgb = format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue);
+        if self.alpha == 255 {
+            rgb
+        } else {
+            format!("{rgb}{:02x}", self.alpha)
+        }
+    }
+}
+
+/// Parse a color in hex, `rgb` or `rgba` notation
+/// # Errors
+/// Returns [`Error::InvalidColor`] if the input is not a valid color
 pub fn parse_color(input: &str) -> Result<Color, Error> {
     if input.starts_with('#') {
         parse_hex(input)
@@ -165,6 +191,7 @@ fn parse_rgb(input: &str) -> Result<Color, Error> {
 #[cfg(test)]
 mod color_tests {
     use super::{Color, parse_color};
+    use parser::{FromConfigValue, ToConfigValue};
 
     #[test]
     fn parse_hex() {
@@ -200,4 +227,18 @@ mod color_tests {
         let expected = Color::rgb(255, 255, 255);
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn from_config_value() {
+        let result = Color::from_config_value("rgb(0,0,0)").unwrap();
+        assert_eq!(result, Color::BLACK);
+        let result = Color::from_config_value("black").unwrap_err();
+        assert!(result.to_string().starts_with("Expected color"))
+    }
+
+    #[test]
+    fn to_config_value() {
+        assert_eq!(Color::rgb(255, 0, 16).to_config_value(), "#ff0010");
+        assert_eq!(Color::TRANSPARENT.to_config_value(), "#00000000")
+    }
 }
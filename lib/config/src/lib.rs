@@ -1,84 +1,275 @@
-use parser::parse_file;
-use std::{env::home_dir, path::PathBuf};
+pub use parser::Schema;
+use parser::{
+    ConfigBuilder, ConfigDocument, FromConfigValue, ToConfig, ToConfigValue, parse_file_with,
+};
+use std::{env::home_dir, fmt, path::PathBuf};
 
 mod colors;
 mod errors;
-mod parse;
+mod sizes;
+pub mod xdg;
 pub use colors::Color;
 pub use errors::Error;
-use parse::AppConfigBuilder;
+pub use parser::Override;
+pub use sizes::{DEFAULT_TEXT_SIZE, EntryMetrics, Metrics, REFERENCE_WINDOW, Scale, Size};
 
-/// Configuration for entries shown in the window
+/// Order of the entries in the menu
+#[derive(FromConfigValue, ToConfigValue, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    /// Order of the entry files by name
+    Files,
+    /// Alphabetical by title, ignoring case
+    Alphabetical,
+    /// By the `order` key of the entries, entries without it come last
+    Order,
+    /// Most recently launched first
+    Recent,
+    /// Most frequently launched first
+    Frequent,
+    /// Frequently and recently launched first, see `doc/entries.md`
+    Frecency,
+}
+
+/// How the categories of the entries are shown
+#[derive(FromConfigValue, ToConfigValue, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CategoryLayout {
+    /// All entries in a single grid, in sort order regardless of their category
+    Flat,
+    /// A header above the entries of each category
+    Headers,
+    /// A tab bar with one tab per category, showing the entries of the selected one
+    Tabs,
+}
+
+/// How the entries of the menu are arranged
+#[derive(FromConfigValue, ToConfigValue, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuLayout {
+    /// Rows of entries filling the width of the window
+    Grid,
+    /// One entry per row with icon, title and description
+    List,
+    /// A single row scrolling with the selection, the selected entry is enlarged
+    Carousel,
+}
+
+/// Arrangement of icon and title inside an entry
+#[derive(FromConfigValue, ToConfigValue, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileLayout {
+    /// The icon above the title
+    IconAbove,
+    /// Only the icon
+    IconOnly,
+    /// Only the title
+    TitleOnly,
+    /// The icon left of the title, for wide entries in a list
+    IconLeft,
+    /// The poster (or icon) filling the entry, with the title on top of its lower edge
+    Poster,
+}
+
+/// Horizontal alignment of the title of an entry
+#[derive(FromConfigValue, ToConfigValue, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// How titles longer than their entry are shown
+#[derive(FromConfigValue, ToConfigValue, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleOverflow {
+    /// Cut off at the edge of the entry
+    Clip,
+    /// Continued on the next lines
+    Wrap,
+    /// Shortened, ending with `…`
+    Ellipsis,
+}
+
+/// Weight of a font
+#[derive(FromConfigValue, ToConfigValue, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontWeight {
+    Thin,
+    ExtraLight,
+    Light,
+    Normal,
+    Medium,
+    Semibold,
+    Bold,
+    ExtraBold,
+    Black,
+}
+
+/// How entries are shown in the menu
+#[derive(ConfigBuilder, Debug, PartialEq)]
+#[config(error = Error)]
 pub struct EntryConfig {
-    /// Background color
+    /// Background color of an entry
+    #[config(default = Color::TRANSPARENT)]
     pub background: Color,
-    /// Background color when selected
+    /// Background color of the selected entry
+    #[config(default = Color::WHITE)]
     pub background_active: Color,
-    /// Text color
+    /// Text color of an entry
+    #[config(default = Color::BLACK)]
     pub text_color: Color,
-    /// font size
-    pub text_size: f32,
-    /// Border radius
-    pub border_radius: f32,
-    /// width of an entry
-    pub width: f32,
-    /// height of an entry
-    pub height: f32,
+    /// Text size of an entry
+    #[config(default = Size::Px(12.0))]
+    pub text_size: Size,
+    /// Font family of an entry, the font of the window if not set
+    #[config(optional)]
+    pub font: Option<String>,
+    /// Font weight of an entry
+    #[config(default = FontWeight::Normal)]
+    pub font_weight: FontWeight,
+    /// Font file to load, `font` defaults to its family
+    #[config(optional)]
+    pub font_file: Option<PathBuf>,
+    /// Border radius of an entry
+    #[config(default = Size::Px(0.0))]
+    pub border_radius: Size,
+    /// Width of an entry
+    #[config(default = Size::Px(100.0))]
+    pub width: Size,
+    /// Height of an entry
+    #[config(default = Size::Px(100.0))]
+    pub height: Size,
+    /// Arrangement of icon and title
+    #[config(default = TileLayout::IconAbove)]
+    pub tile: TileLayout,
+    /// Horizontal alignment of the title
+    #[config(default = TitleAlign::Center)]
+    pub title_align: TitleAlign,
+    /// How titles longer than the entry are shown
+    #[config(default = TitleOverflow::Clip)]
+    pub title_overflow: TitleOverflow,
 }
 
-/// Configuration for the App, loaded from a file
+/// App configuration, the keys before the first section header configure the window
+#[derive(ConfigBuilder, Debug, PartialEq)]
+#[config(error = Error)]
 pub struct AppConfig {
-    /// Configuration for entries
+    /// How entries are shown in the menu
+    #[config(nested, section = "Entries")]
     pub entries: EntryConfig,
-    /// Background color
+    /// Background color of the window
+    #[config(default = Color::BLACK)]
     pub background: Color,
-    /// Text Color
+    /// Text color of the window, does not apply to entries
+    #[config(default = Color::WHITE)]
     pub text_color: Color,
-    /// Font size
-    pub text_size: f32,
-    /// Number of columns to show
-    /// calculated based on window size if `None`
+    /// Factor for all sizes given in pixels, `auto` derives it from the window size
+    #[config(default = Scale::Factor(1.0))]
+    pub scale: Scale,
+    /// Text size of the window, does not apply to entries
+    #[config(default = Size::Px(12.0))]
+    pub text_size: Size,
+    /// Font family of the window, the default font if not set
+    #[config(optional)]
+    pub font: Option<String>,
+    /// Font weight of the window, does not apply to entries
+    #[config(default = FontWeight::Normal)]
+    pub font_weight: FontWeight,
+    /// Font file to load, `font` defaults to its family
+    #[config(optional)]
+    pub font_file: Option<PathBuf>,
+    /// Number of entries per row, as many as fit into the window if not set
+    #[config(optional)]
     pub columns: Option<u64>,
-    /// Padding from the window sides
-    pub padding: f32,
-    /// Window Height
+    /// Center the rows of the grid in the window instead of aligning them left
+    #[config(default = false)]
+    pub center_grid: bool,
+    /// Padding between the window border and its contents
+    #[config(default = Size::Px(0.0))]
+    pub padding: Size,
+    /// Window height, uses the default window size if `0`
+    #[config(default = 0.0)]
     pub height: f32,
-    /// Window Width
+    /// Window width, uses the default window size if `0`
+    #[config(default = 0.0)]
     pub width: f32,
     /// Gap between columns
-    pub column_gap: f32,
+    #[config(default = Size::Px(10.0))]
+    pub column_gap: Size,
     /// Gap between rows
-    pub row_gap: f32,
+    #[config(default = Size::Px(10.0))]
+    pub row_gap: Size,
+    /// How the entries of the menu are arranged
+    #[config(default = MenuLayout::Grid)]
+    pub layout: MenuLayout,
+    /// Order of the entries, entries of the same category are kept together
+    #[config(default = SortMode::Files)]
+    pub sort: SortMode,
+    /// Number of recently launched entries shown in a row above the menu, `0` hides the row
+    #[config(default = 0)]
+    pub recent: usize,
+    /// Order of the categories, categories that are not listed follow in order of their first entry
+    #[config(default = Vec::new())]
+    pub categories: Vec<String>,
+    /// How categories are shown
+    #[config(default = CategoryLayout::Flat)]
+    pub category_layout: CategoryLayout,
 }
 
 impl AppConfig {
     /// Load App Config from given locations
-    /// tries to load files in order
+    /// tries to load files in order, applying `overrides` to the loaded file
     /// # Errors
     /// Returns an error if none of the given paths could be loaded
     /// that is for each path it either does not exist or [`Self::from_file`] returned an error
-    pub fn load(config_paths: &[&str]) -> Result<Self, Error> {
-        for path_name in config_paths {
-            let path = expand_user(path_name)?;
-
+    /// if any existing file returned an error, the first such error is returned
+    pub fn load(config_paths: &[PathBuf], overrides: &[Override]) -> Result<Self, Error> {
+        let mut first_err = None;
+        for path in config_paths {
             if !path.exists() {
                 continue;
             }
-            let conf = Self::from_file(path);
-            if conf.is_ok() {
-                return conf;
+            match Self::from_file(path.clone(), overrides) {
+                Ok(conf) => return Ok(conf),
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
             }
         }
-        Err(Error::NoConfigFound(
-            config_paths.iter().map(|path| (*path).to_owned()).collect(),
-        ))
+        Err(first_err.unwrap_or_else(|| {
+            Error::NoConfigFound(
+                config_paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect(),
+            )
+        }))
+    }
+
+    /// Sections and keys of the config, with their types, defaults and descriptions
+    #[must_use]
+    pub fn schema() -> Schema {
+        AppConfigBuilder::schema()
     }
 
-    /// Parse config from a file
+    /// Parse config from a file and apply the given overrides
     /// # Errors
-    /// Returns an error if the file could not be read or if the contents could not be parsed
-    pub fn from_file(path: PathBuf) -> Result<Self, Error> {
-        parse_file::<AppConfigBuilder>(path)
+    /// Returns an error if the file could not be read, if the contents could not be parsed
+    /// or if an override could not be applied
+    pub fn from_file(path: PathBuf, overrides: &[Override]) -> Result<Self, Error> {
+        parse_file_with::<AppConfigBuilder>(path, overrides)
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfigBuilder::default().build()
+    }
+}
+
+impl fmt::Display for AppConfig {
+    /// Write the config in the config file format, with each key preceded by its description
+    /// `AppConfig::default().to_string()` is a fully commented default config
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut document = ConfigDocument::new();
+        document.push_comment("tvmenu config, see doc/config.md for all options");
+        self.write_config(&mut document);
+        write!(f, "{document}")
     }
 }
 
@@ -106,3 +297,35 @@ pub fn expand_user(path_name: &str) -> Result<PathBuf, Error> {
         Ok(PathBuf::from(path_name))
     }
 }
+
+#[cfg(test)]
+mod config_tests {
+    use super::{AppConfig, Error};
+    use std::{error::Error as _, fs::write};
+
+    #[test]
+    fn default_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tvmenu.conf");
+        write(&path, AppConfig::default().to_string()).unwrap();
+        let result = AppConfig::from_file(path, &[]).unwrap();
+        assert_eq!(result, AppConfig::default())
+    }
+
+    #[test]
+    fn invalid_value_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tvmenu.conf");
+        write(&path, "[Entries]\nbackground = blue").unwrap();
+        let Err(Error::Parse(err @ parser::Error::InvalidValue { .. })) =
+            AppConfig::from_file(path, &[])
+        else {
+            panic!("expected invalid value")
+        };
+        let source = err.source().and_then(|source| source.downcast_ref());
+        assert!(matches!(
+            source,
+            Some(Error::Parse(parser::Error::InvalidType { .. }))
+        ))
+    }
+}
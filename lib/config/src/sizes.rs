@@ -0,0 +1,247 @@
+use crate::{AppConfig, EntryConfig};
+use parser::{Error, FromConfigValue, ToConfigValue};
+
+/// Text size `em` refers to for the text size of the window, the default text size of iced
+pub const DEFAULT_TEXT_SIZE: f32 = 16.0;
+
+/// Window size for which `scale = auto` keeps pixel sizes unchanged
+pub const REFERENCE_WINDOW: (f32, f32) = (1920.0, 1080.0);
+
+/// A size in pixels, in percent of the window or relative to the text size
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Size {
+    /// Pixels, multiplied by [`Scale`], e.g. `12` or `12px`
+    Px(f32),
+    /// Percent of the window width or height, e.g. `5%`
+    Percent(f32),
+    /// Multiple of the text size, e.g. `1.5em`
+    Em(f32),
+}
+
+impl Size {
+    /// The size in pixels
+    /// `scale` is the factor for pixels, `percent_of` the window width or height
+    /// and `em` the text size in pixels
+    #[must_use]
+    pub fn to_px(self, scale: f32, percent_of: f32, em: f32) -> f32 {
+        match self {
+            Self::Px(px) => px * scale,
+            Self::Percent(percent) => percent / 100.0 * percent_of,
+            Self::Em(factor) => factor * em,
+        }
+    }
+}
+
+impl FromConfigValue for Size {
+    fn expected() -> String {
+        "size (e.g. 12, 12px, 5% or 1.5em)".to_owned()
+    }
+
+    fn from_config_value(value: &str) -> Result<Self, Error> {
+        let value = value.trim();
+        let (number, size): (&str, fn(f32) -> Self) = if let Some(num) = value.strip_suffix('%') {
+            (num, Self::Percent)
+        } else if let Some(num) = value.strip_suffix("em") {
+            (num, Self::Em)
+        } else {
+            (value.strip_suffix("px").unwrap_or(value), Self::Px)
+        };
+        number
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|num| num.is_finite() && *num >= 0.0)
+            .map(size)
+            .ok_or_else(|| Error::invalid_type(value, &Self::expected()))
+    }
+}
+
+impl ToConfigValue for Size {
+    fn to_config_value(&self) -> String {
+        match self {
+            Self::Px(px) => px.to_string(),
+            Self::Percent(percent) => format!("{percent}%"),
+            Self::Em(factor) => format!("{factor}em"),
+        }
+    }
+}
+
+/// Factor for all sizes given in pixels
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Scale {
+    /// Derived from the window size only, see [`REFERENCE_WINDOW`]
+    /// the scale factor of the display is applied on top by iced
+    Auto,
+    /// A fixed factor
+    Factor(f32),
+}
+
+impl Scale {
+    /// The factor for a window of `width` by `height` logical pixels
+    /// `auto` scales a window with the aspect ratio of [`REFERENCE_WINDOW`] to it
+    #[must_use]
+    pub fn factor(self, width: f32, height: f32) -> f32 {
+        match self {
+            Self::Auto => (width / REFERENCE_WINDOW.0).min(height / REFERENCE_WINDOW.1),
+            Self::Factor(factor) => factor,
+        }
+    }
+}
+
+impl FromConfigValue for Scale {
+    fn expected() -> String {
+        "auto or a number".to_owned()
+    }
+
+    fn from_config_value(value: &str) -> Result<Self, Error> {
+        if value.trim().eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        value
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|factor| factor.is_finite() && *factor > 0.0)
+            .map(Self::Factor)
+            .ok_or_else(|| Error::invalid_type(value, &Self::expected()))
+    }
+}
+
+impl ToConfigValue for Scale {
+    fn to_config_value(&self) -> String {
+        match self {
+            Self::Auto => "auto".to_owned(),
+            Self::Factor(factor) => factor.to_string(),
+        }
+    }
+}
+
+/// Sizes of the config in pixels for a window size, see [`AppConfig::metrics`]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Metrics {
+    /// Width of the window
+    pub window_width: f32,
+    /// Height of the window
+    pub window_height: f32,
+    /// Factor for sizes in pixels
+    pub scale: f32,
+    /// Text size of the window
+    pub text_size: f32,
+    /// Padding between the window border and its contents
+    pub padding: f32,
+    /// Gap between columns
+    pub column_gap: f32,
+    /// Gap between rows
+    pub row_gap: f32,
+    /// Sizes of the entries
+    pub entries: EntryMetrics,
+}
+
+/// Sizes of the `[Entries]` section in pixels
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EntryMetrics {
+    /// Text size of an entry
+    pub text_size: f32,
+    /// Border radius of an entry
+    pub border_radius: f32,
+    /// Width of an entry
+    pub width: f32,
+    /// Height of an entry
+    pub height: f32,
+}
+
+impl Metrics {
+    /// `size` in pixels, percentages refer to the window width
+    #[must_use]
+    pub fn horizontal(&self, size: Size, em: f32) -> f32 {
+        size.to_px(self.scale, self.window_width, em)
+    }
+
+    /// `size` in pixels, percentages refer to the window height
+    #[must_use]
+    pub fn vertical(&self, size: Size, em: f32) -> f32 {
+        size.to_px(self.scale, self.window_height, em)
+    }
+
+    /// Sizes of an entry in pixels, `em` of the entry refers to the text size of the window
+    #[must_use]
+    pub fn entry(&self, entry: &EntryConfig) -> EntryMetrics {
+        let text_size = self.vertical(entry.text_size, self.text_size);
+        EntryMetrics {
+            text_size,
+            border_radius: self.vertical(entry.border_radius, text_size),
+            width: self.horizontal(entry.width, text_size),
+            height: self.vertical(entry.height, text_size),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Sizes of the config in pixels for a window of `width` by `height` pixels
+    /// percentages refer to the window width for widths, gaps between columns and the padding
+    /// and to the window height otherwise
+    #[must_use]
+    pub fn metrics(&self, width: f32, height: f32) -> Metrics {
+        let mut metrics = Metrics {
+            window_width: width,
+            window_height: height,
+            scale: self.scale.factor(width, height),
+            text_size: 0.0,
+            padding: 0.0,
+            column_gap: 0.0,
+            row_gap: 0.0,
+            entries: EntryMetrics {
+                text_size: 0.0,
+                border_radius: 0.0,
+                width: 0.0,
+                height: 0.0,
+            },
+        };
+        metrics.text_size = metrics.vertical(self.text_size, DEFAULT_TEXT_SIZE * metrics.scale);
+        metrics.padding = metrics.horizontal(self.padding, metrics.text_size);
+        metrics.column_gap = metrics.horizontal(self.column_gap, metrics.text_size);
+        metrics.row_gap = metrics.vertical(self.row_gap, metrics.text_size);
+        metrics.entries = metrics.entry(&self.entries);
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod size_tests {
+    use super::{Scale, Size};
+    use crate::AppConfig;
+    use parser::{FromConfigValue, ToConfigValue};
+
+    #[test]
+    fn parse_size() {
+        assert_eq!(Size::from_config_value("12").unwrap(), Size::Px(12.0));
+        assert_eq!(Size::from_config_value("12px").unwrap(), Size::Px(12.0));
+        assert_eq!(Size::from_config_value("5%").unwrap(), Size::Percent(5.0));
+        assert_eq!(Size::from_config_value("1.5em").unwrap(), Size::Em(1.5));
+        assert!(Size::from_config_value("-1").is_err());
+        assert!(Size::from_config_value("1pt").is_err());
+        assert_eq!(Size::Em(1.5).to_config_value(), "1.5em");
+        assert_eq!(Scale::from_config_value("Auto").unwrap(), Scale::Auto);
+        assert!(Scale::from_config_value("0").is_err())
+    }
+
+    #[test]
+    fn metrics() {
+        let mut config = AppConfig {
+            scale: Scale::Auto,
+            text_size: Size::Percent(2.0),
+            padding: Size::Em(1.0),
+            ..AppConfig::default()
+        };
+        config.entries.width = Size::Percent(10.0);
+        config.entries.text_size = Size::Em(0.5);
+        let metrics = config.metrics(3840.0, 2160.0);
+        assert!((metrics.scale - 2.0).abs() < f32::EPSILON);
+        assert!((metrics.text_size - 43.2).abs() < 0.001);
+        assert!((metrics.padding - 43.2).abs() < 0.001);
+        assert!((metrics.column_gap - 20.0).abs() < f32::EPSILON);
+        assert!((metrics.entries.width - 384.0).abs() < f32::EPSILON);
+        assert!((metrics.entries.text_size - 21.6).abs() < 0.001);
+        assert!((metrics.entries.height - 200.0).abs() < f32::EPSILON)
+    }
+}
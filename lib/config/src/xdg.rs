@@ -0,0 +1,156 @@
+use std::{
+    env::{home_dir, split_paths, var_os},
+    ffi::OsString,
+    path::PathBuf,
+};
+
+/// Name of the app subdirectory inside the XDG base directories
+pub const APP_DIR: &str = "tvmenu";
+
+/// Read an absolute path from an environment variable
+/// relative paths are invalid according to the spec and are ignored
+fn env_dir(var: &str) -> Option<PathBuf> {
+    parse_dir(var_os(var))
+}
+
+/// Parse the value of a variable holding a single directory, see [`env_dir`]
+fn parse_dir(value: Option<OsString>) -> Option<PathBuf> {
+    value.map(PathBuf::from).filter(|path| path.is_absolute())
+}
+
+/// Read a `:`-separated list of absolute paths from an environment variable
+/// falls back to `default` if the variable is unset or contains no valid paths
+fn env_dirs(var: &str, default: &[&str]) -> Vec<PathBuf> {
+    parse_dirs(var_os(var), default)
+}
+
+/// Parse the value of a variable holding a list of directories, see [`env_dirs`]
+fn parse_dirs(value: Option<OsString>, default: &[&str]) -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = value
+        .map(|val| {
+            split_paths(&val)
+                .filter(|path| path.is_absolute())
+                .collect()
+        })
+        .unwrap_or_default();
+    if dirs.is_empty() {
+        default.iter().map(PathBuf::from).collect()
+    } else {
+        dirs
+    }
+}
+
+/// Read a base directory from `var`, falling back to `default` relative to the home directory
+fn home_relative(var: &str, default: &str) -> Option<PathBuf> {
+    env_dir(var).or_else(|| home_dir().map(|home| home.join(default)))
+}
+
+/// `$XDG_CONFIG_HOME`, defaults to `~/.config`
+#[must_use]
+pub fn config_home() -> Option<PathBuf> {
+    home_relative("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_DATA_HOME`, defaults to `~/.local/share`
+#[must_use]
+pub fn data_home() -> Option<PathBuf> {
+    home_relative("XDG_DATA_HOME", ".local/share")
+}
+
+/// `$XDG_STATE_HOME`, defaults to `~/.local/state`
+#[must_use]
+pub fn state_home() -> Option<PathBuf> {
+    home_relative("XDG_STATE_HOME", ".local/state")
+}
+
+/// `$XDG_CONFIG_DIRS`, defaults to `/etc/xdg`
+#[must_use]
+pub fn config_dirs() -> Vec<PathBuf> {
+    env_dirs("XDG_CONFIG_DIRS", &["/etc/xdg"])
+}
+
+/// `$XDG_DATA_DIRS`, defaults to `/usr/local/share:/usr/share`
+#[must_use]
+pub fn data_dirs() -> Vec<PathBuf> {
+    env_dirs("XDG_DATA_DIRS", &["/usr/local/share", "/usr/share"])
+}
+
+/// Config directories in order of preference
+/// that is `$XDG_CONFIG_HOME` followed by `$XDG_CONFIG_DIRS`
+#[must_use]
+pub fn config_search_dirs() -> Vec<PathBuf> {
+    search_dirs(config_home(), config_dirs())
+}
+
+/// Data directories in order of preference
+/// that is `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`
+#[must_use]
+pub fn data_search_dirs() -> Vec<PathBuf> {
+    search_dirs(data_home(), data_dirs())
+}
+
+/// The user directory `home` followed by the system directories `dirs`
+fn search_dirs(home: Option<PathBuf>, dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    home.into_iter().chain(dirs).collect()
+}
+
+/// Find the first existing file `name` inside the app directory of the given base directories
+#[must_use]
+pub fn find_file(base_dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    base_dirs
+        .iter()
+        .map(|dir| dir.join(APP_DIR).join(name))
+        .find(|path| path.exists())
+}
+
+#[cfg(test)]
+mod xdg_tests {
+    use super::{parse_dir, parse_dirs, search_dirs};
+    use std::{ffi::OsString, path::PathBuf};
+
+    fn value(val: &str) -> Option<OsString> {
+        Some(OsString::from(val))
+    }
+
+    #[test]
+    fn single_dir() {
+        assert_eq!(
+            parse_dir(value("/home/tv/.config")),
+            Some("/home/tv/.config".into())
+        );
+        assert_eq!(parse_dir(value("relative/config")), None);
+        assert_eq!(parse_dir(value("")), None);
+        assert_eq!(parse_dir(None), None)
+    }
+
+    #[test]
+    fn dir_list() {
+        let default = ["/etc/xdg"];
+        let dirs = parse_dirs(value("/opt/xdg:relative:/etc/xdg2"), &default);
+        assert_eq!(dirs, vec![PathBuf::from("/opt/xdg"), "/etc/xdg2".into()]);
+        assert_eq!(
+            parse_dirs(value("relative"), &default),
+            vec![PathBuf::from("/etc/xdg")]
+        );
+        assert_eq!(
+            parse_dirs(value(""), &default),
+            vec![PathBuf::from("/etc/xdg")]
+        );
+        assert_eq!(parse_dirs(None, &default), vec![PathBuf::from("/etc/xdg")])
+    }
+
+    #[test]
+    fn search_order() {
+        let system = vec![PathBuf::from("/etc/xdg"), "/opt/xdg".into()];
+        let dirs = search_dirs(Some("/home/tv/.config".into()), system.clone());
+        assert_eq!(
+            dirs,
+            vec![
+                PathBuf::from("/home/tv/.config"),
+                "/etc/xdg".into(),
+                "/opt/xdg".into()
+            ]
+        );
+        assert_eq!(search_dirs(None, system.clone()), system)
+    }
+}
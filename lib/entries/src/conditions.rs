@@ -0,0 +1,113 @@
+use std::{
+    env::{split_paths, var_os},
+    fs::read_to_string,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+/// Files containing the host name, in order of preference
+const HOSTNAME_FILES: [&str; 2] = ["/proc/sys/kernel/hostname", "/etc/hostname"];
+
+/// Conditions for showing an entry, unset conditions are met
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Conditions {
+    /// `hidden`, never show the entry
+    pub hidden: Option<bool>,
+    /// `only-if-exists`, only show the entry if the path exists
+    pub only_if_exists: Option<PathBuf>,
+    /// `try-exec`, only show the entry if the program is an executable file or found in `$PATH`
+    pub try_exec: Option<String>,
+    /// `only-on-host`, only show the entry on one of the hosts
+    pub only_on_host: Option<Vec<String>>,
+}
+
+impl Conditions {
+    /// Take conditions that are not set from `defaults`
+    #[must_use]
+    pub fn or(self, defaults: &Self) -> Self {
+        Self {
+            hidden: self.hidden.or(defaults.hidden),
+            only_if_exists: self
+                .only_if_exists
+                .or_else(|| defaults.only_if_exists.clone()),
+            try_exec: self.try_exec.or_else(|| defaults.try_exec.clone()),
+            only_on_host: self.only_on_host.or_else(|| defaults.only_on_host.clone()),
+        }
+    }
+
+    /// Whether all conditions are met on this machine
+    #[must_use]
+    pub fn are_met(&self) -> bool {
+        self.hidden != Some(true)
+            && self
+                .only_if_exists
+                .as_ref()
+                .is_none_or(|path| path.exists())
+            && self.try_exec.as_deref().is_none_or(find_executable)
+            && self.only_on_host.as_ref().is_none_or(|hosts| {
+                hostname()
+                    .is_some_and(|name| hosts.iter().any(|host| host.eq_ignore_ascii_case(&name)))
+            })
+    }
+}
+
+/// Whether `program` is an executable file, programs without `/` are searched in `$PATH`
+fn find_executable(program: &str) -> bool {
+    if program.contains('/') {
+        return is_executable(Path::new(program));
+    }
+    var_os("PATH")
+        .is_some_and(|paths| split_paths(&paths).any(|dir| is_executable(&dir.join(program))))
+}
+
+fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// Name of this machine
+fn hostname() -> Option<String> {
+    HOSTNAME_FILES
+        .iter()
+        .find_map(|file| read_to_string(file).ok())
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod conditions_tests {
+    use super::{Conditions, hostname};
+    use std::path::PathBuf;
+
+    #[test]
+    fn conditions() {
+        assert!(Conditions::default().are_met());
+        let hidden = Conditions {
+            hidden: Some(true),
+            ..Conditions::default()
+        };
+        assert!(!hidden.are_met());
+        assert!(!Conditions::default().or(&hidden).are_met());
+        let exists = |path: &str| Conditions {
+            only_if_exists: Some(PathBuf::from(path)),
+            ..Conditions::default()
+        };
+        assert!(exists("/").are_met());
+        assert!(!exists("/does/not/exist").are_met());
+        let exec = |program: &str| Conditions {
+            try_exec: Some(program.to_owned()),
+            ..Conditions::default()
+        };
+        assert!(exec("sh").are_met());
+        assert!(exec("/bin/sh").are_met());
+        assert!(!exec("tvmenu-does-not-exist").are_met());
+        let host = |name: String| Conditions {
+            only_on_host: Some(vec!["other-host".to_owned(), name]),
+            ..Conditions::default()
+        };
+        if let Some(name) = hostname() {
+            assert!(host(name.to_uppercase()).are_met());
+        }
+        assert!(!host("tvmenu-no-such-host".to_owned()).are_met())
+    }
+}
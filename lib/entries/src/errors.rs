@@ -6,12 +6,15 @@ use std::{
 #[derive(Debug)]
 pub enum Error {
     ReadDir { path: PathBuf, reason: String },
-    UnknownSection(String),
+    ReadFile { path: PathBuf, reason: String },
+    WriteFile { path: PathBuf, reason: String },
     UnknownKey(String),
     IconNotFound(String),
+    UnterminatedQuote(String),
     Parser(parser::Error),
     Config(config::Error),
     NoEntriesFound { prev_errors: Vec<(PathBuf, Self)> },
+    FolderCycle(PathBuf),
 }
 
 impl Error {
@@ -23,6 +26,24 @@ impl Error {
             reason: err.to_string(),
         }
     }
+
+    /// Create an [`Error::ReadFile`] from given path and [`io::Error`]
+    #[must_use]
+    pub fn read_file(err: &io::Error, path: &Path) -> Self {
+        Self::ReadFile {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        }
+    }
+
+    /// Create an [`Error::WriteFile`] from given path and [`io::Error`]
+    #[must_use]
+    pub fn write_file(err: &io::Error, path: &Path) -> Self {
+        Self::WriteFile {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -31,9 +52,17 @@ impl fmt::Display for Error {
             Self::ReadDir { path, reason } => {
                 write!(f, "Could not read dir {}:\n{reason}", path.display())
             }
-            Self::UnknownSection(sec) => write!(f, "Menu Entry cannot have section {sec}"),
+            Self::ReadFile { path, reason } => {
+                write!(f, "Could not read file {}:\n{reason}", path.display())
+            }
+            Self::WriteFile { path, reason } => {
+                write!(f, "Could not write file {}:\n{reason}", path.display())
+            }
             Self::UnknownKey(key) => write!(f, "Menu Entry cannot have key {key}"),
             Self::IconNotFound(name) => write!(f, "Could not find icon {name}"),
+            Self::UnterminatedQuote(command) => {
+                write!(f, "Command {command} has an unterminated quote")
+            }
             Self::Parser(err) => err.fmt(f),
             Self::NoEntriesFound { prev_errors } => write!(
                 f,
@@ -45,6 +74,7 @@ impl fmt::Display for Error {
                     .join(","),
             ),
             Self::Config(err) => err.fmt(f),
+            Self::FolderCycle(path) => write!(f, "Folder {} contains itself", path.display()),
         }
     }
 }
@@ -0,0 +1,117 @@
+use crate::{
+    Error, MenuEntry,
+    history::{escape_title, unescape_title},
+};
+use std::{
+    fmt,
+    fs::{create_dir_all, read_to_string, write},
+    io::ErrorKind,
+    path::Path,
+};
+
+/// Entries pinned or unpinned at runtime, overriding the `favorite` key of the entry
+///
+/// The favorites file has one line per entry, a `+` (pinned) or `-` (unpinned)
+/// followed by the title of the entry, escaped like in the history file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Favorites {
+    overrides: Vec<(String, bool)>,
+}
+
+impl Favorites {
+    /// Load the favorites from a file, a missing file means no overrides
+    /// # Errors
+    /// Returns an error if the file exists but could not be read
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match read_to_string(path) {
+            Ok(source) => Ok(Self::parse(&source)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(Error::read_file(&err, path)),
+        }
+    }
+
+    /// Parse the contents of a favorites file, malformed lines are ignored
+    #[must_use]
+    pub fn parse(source: &str) -> Self {
+        let overrides = source
+            .lines()
+            .filter_map(|line| {
+                let (pinned, title) = match line.split_at_checked(1)? {
+                    ("+", title) => (true, title),
+                    ("-", title) => (false, title),
+                    _ => return None,
+                };
+                (!title.is_empty()).then(|| (unescape_title(title), pinned))
+            })
+            .collect();
+        Self { overrides }
+    }
+
+    /// Write the favorites to a file, creating its directory if needed
+    /// # Errors
+    /// Returns an error if the directory could not be created or the file could not be written
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir).map_err(|err| Error::write_file(&err, path))?;
+        }
+        write(path, self.to_string()).map_err(|err| Error::write_file(&err, path))
+    }
+
+    /// Whether `entry` is pinned, either by its `favorite` key or at runtime
+    #[must_use]
+    pub fn is_favorite(&self, entry: &MenuEntry) -> bool {
+        self.overrides
+            .iter()
+            .find(|(title, _)| *title == entry.title)
+            .map_or(entry.favorite, |(_, pinned)| *pinned)
+    }
+
+    /// Pin `entry` if it is not pinned and unpin it otherwise
+    pub fn toggle(&mut self, entry: &MenuEntry) {
+        let pinned = !self.is_favorite(entry);
+        self.overrides.retain(|(title, _)| *title != entry.title);
+        if pinned != entry.favorite {
+            self.overrides.push((entry.title.clone(), pinned));
+        }
+    }
+}
+
+impl fmt::Display for Favorites {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (title, pinned) in &self.overrides {
+            let pinned = if *pinned { '+' } else { '-' };
+            writeln!(f, "{pinned}{}", escape_title(title))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod favorites_tests {
+    use super::Favorites;
+    use crate::MenuEntry;
+
+    fn entry(title: &str, favorite: bool) -> MenuEntry {
+        MenuEntry {
+            favorite,
+            ..MenuEntry::test(title)
+        }
+    }
+
+    #[test]
+    fn toggle() {
+        let kodi = entry("Kodi", true);
+        let web = entry("Web", false);
+        let mut favorites = Favorites::parse("-Kodi\ninvalid\n");
+        assert!(!favorites.is_favorite(&kodi));
+        favorites.toggle(&kodi);
+        favorites.toggle(&web);
+        assert!(favorites.is_favorite(&kodi));
+        assert!(favorites.is_favorite(&web));
+        assert_eq!(favorites.to_string(), "+Web\n");
+        assert_eq!(Favorites::parse(&favorites.to_string()), favorites);
+        favorites.toggle(&entry("Two\nLines", false));
+        assert_eq!(favorites.to_string(), "+Web\n+Two\\nLines\n");
+        assert_eq!(Favorites::parse(&favorites.to_string()), favorites)
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{Error, MenuEntry};
+use std::{
+    cmp::Reverse,
+    fmt,
+    fs::{create_dir_all, read_to_string, remove_file, write},
+    io::ErrorKind,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Launches lose half of their weight for frecency after this many seconds (a week)
+const FRECENCY_HALF_LIFE: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+/// Current time in seconds since the unix epoch
+#[must_use]
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+/// How often and when an entry was launched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Launches {
+    /// Number of launches
+    pub count: u64,
+    /// Time of the last launch, in seconds since the unix epoch
+    pub last: u64,
+}
+
+/// Launches of an entry, identified by its title and command
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Record {
+    title: String,
+    /// `None` for records written before the command was stored, matching any command
+    command: Option<String>,
+    launches: Launches,
+}
+
+impl Record {
+    fn matches(&self, entry: &MenuEntry) -> bool {
+        self.title == entry.title
+            && self
+                .command
+                .as_ref()
+                .is_none_or(|command| *command == entry_command(entry))
+    }
+}
+
+/// Command line of an entry, used to tell apart entries with the same title
+fn entry_command(entry: &MenuEntry) -> String {
+    let mut command = entry.launch.clone();
+    for arg in &entry.args {
+        command.push(' ');
+        command.push_str(arg);
+    }
+    command
+}
+
+/// Launch history of the menu entries, identified by their title and command
+///
+/// The history file has one line per entry, containing the launch count,
+/// the time of the last launch, the title and the command separated by tabs,
+/// see [`escape_title`] for titles containing tabs or line breaks.
+/// Lines without command match every entry with their title
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    entries: Vec<Record>,
+}
+
+impl History {
+    /// Load the history from a file, a missing file is an empty history
+    /// # Errors
+    /// Returns an error if the file exists but could not be read
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match read_to_string(path) {
+            Ok(source) => Ok(Self::parse(&source)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(Error::read_file(&err, path)),
+        }
+    }
+
+    /// Parse the contents of a history file, malformed lines are ignored
+    #[must_use]
+    pub fn parse(source: &str) -> Self {
+        let mut entries: Vec<Record> = source
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(4, '\t');
+                let count = parts.next()?.parse().ok()?;
+                let last = parts.next()?.parse().ok()?;
+                let title = parts.next().filter(|title| !title.is_empty())?;
+                Some(Record {
+                    title: unescape_title(title),
+                    command: parts.next().map(unescape_title),
+                    launches: Launches { count, last },
+                })
+            })
+            .collect();
+        entries.sort_by_key(|record| Reverse(record.launches.last));
+        Self { entries }
+    }
+
+    /// Write the history to a file, creating its directory if needed
+    /// # Errors
+    /// Returns an error if the directory could not be created or the file could not be written
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir).map_err(|err| Error::write_file(&err, path))?;
+        }
+        write(path, self.to_string()).map_err(|err| Error::write_file(&err, path))
+    }
+
+    /// Remove the history file, a missing file is not an error
+    /// # Errors
+    /// Returns an error if the file exists but could not be removed
+    pub fn reset(path: &Path) -> Result<(), Error> {
+        match remove_file(path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(Error::write_file(&err, path)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether no entry was launched
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Titles, commands and launches of all entries, most recently launched first
+    /// the command is `None` for entries recorded by title only
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&str>, Launches)> {
+        self.entries.iter().map(|record| {
+            (
+                record.title.as_str(),
+                record.command.as_deref(),
+                record.launches,
+            )
+        })
+    }
+
+    /// Indices of the launched `entries`, most recently launched first
+    pub fn launched<'a>(&'a self, entries: &'a [MenuEntry]) -> impl Iterator<Item = usize> + 'a {
+        self.entries
+            .iter()
+            .filter_map(|record| entries.iter().position(|entry| record.matches(entry)))
+    }
+
+    /// Launches of `entry`, `None` if it was never launched
+    #[must_use]
+    pub fn get(&self, entry: &MenuEntry) -> Option<Launches> {
+        self.entries
+            .iter()
+            .find(|record| record.matches(entry))
+            .map(|record| record.launches)
+    }
+
+    /// Record a launch of `entry` at `time`
+    /// a record of its title without command is merged into the record of `entry`
+    pub fn record(&mut self, entry: &MenuEntry, time: u64) {
+        let mut launches = Launches::default();
+        self.entries.retain(|record| {
+            let keep = !record.matches(entry);
+            if !keep {
+                launches.count += record.launches.count;
+                launches.last = launches.last.max(record.launches.last);
+            }
+            keep
+        });
+        launches.count += 1;
+        launches.last = launches.last.max(time);
+        self.entries.insert(
+            0,
+            Record {
+                title: entry.title.clone(),
+                command: Some(entry_command(entry)),
+                launches,
+            },
+        );
+    }
+
+    /// Remove all entries titled `title`, returns `false` if none was launched
+    pub fn remove(&mut self, title: &str) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|record| record.title != title);
+        self.entries.len() != len
+    }
+
+    /// Frecency of `entry` at `time`
+    ///
+    /// The launch count, weighted by the age of the last launch so that the weight halves every week
+    #[must_use]
+    pub fn frecency(&self, entry: &MenuEntry, time: u64) -> f64 {
+        self.get(entry).map_or(0.0, |launches| {
+            // counts and ages beyond u32 saturate, the weight is zero long before
+            let count = u32::try_from(launches.count).unwrap_or(u32::MAX);
+            let age = u32::try_from(time.saturating_sub(launches.last)).unwrap_or(u32::MAX);
+            f64::from(count) * (-f64::from(age) / FRECENCY_HALF_LIFE).exp2()
+        })
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for record in &self.entries {
+            write!(
+                f,
+                "{}\t{}\t{}",
+                record.launches.count,
+                record.launches.last,
+                escape_title(&record.title)
+            )?;
+            match &record.command {
+                Some(command) => writeln!(f, "\t{}", escape_title(command))?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escape a title to fit on one line of the history or favorites file
+/// backslashes, tabs and line breaks are written as `\\`, `\t`, `\n` and `\r`
+pub fn escape_title(title: &str) -> String {
+    title
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Reverse [`escape_title`], unknown escapes are kept as they are
+pub fn unescape_title(title: &str) -> String {
+    let mut unescaped = String::with_capacity(title.len());
+    let mut chars = title.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') | None => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::{History, Launches};
+    use crate::MenuEntry;
+
+    fn app(title: &str, launch: &str) -> MenuEntry {
+        MenuEntry {
+            launch: launch.to_owned(),
+            ..MenuEntry::test(title)
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let history =
+            History::parse("3\t1700000000\tKodi\ninvalid\n1\t1700000100\tWeb\\tBrowser\tfirefox\n");
+        assert_eq!(
+            history.get(&app("Kodi", "kodi")),
+            Some(Launches {
+                count: 3,
+                last: 1_700_000_000
+            })
+        );
+        assert_eq!(
+            history
+                .get(&app("Web\tBrowser", "firefox"))
+                .map(|l| l.count),
+            Some(1)
+        );
+        assert_eq!(history.get(&app("Web\tBrowser", "chromium")), None);
+        assert_eq!(history.get(&app("invalid", "")), None)
+    }
+
+    #[test]
+    fn record_and_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+        let mut history = History::default();
+        history.record(&app("Kodi", "kodi"), 100);
+        history.record(&app("Web", "firefox"), 200);
+        history.record(&app("Kodi", "kodi"), 300);
+        assert_eq!(
+            history.iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    "Kodi",
+                    Some("kodi"),
+                    Launches {
+                        count: 2,
+                        last: 300
+                    }
+                ),
+                (
+                    "Web",
+                    Some("firefox"),
+                    Launches {
+                        count: 1,
+                        last: 200
+                    }
+                )
+            ]
+        );
+        history.save(&path).unwrap();
+        assert_eq!(History::load(&path).unwrap(), history);
+        History::reset(&path).unwrap();
+        assert!(History::load(&path).unwrap().is_empty())
+    }
+
+    #[test]
+    fn same_title() {
+        let entries = [app("Play", "kodi"), app("Play", "mpv")];
+        // a record without command is taken over by the first entry launched
+        let mut history = History::parse("2\t100\tPlay\n");
+        assert_eq!(history.launched(&entries).collect::<Vec<_>>(), vec![0]);
+        history.record(&entries[1], 200);
+        history.record(&entries[1], 300);
+        assert_eq!(history.get(&entries[1]).map(|l| l.count), Some(4));
+        assert_eq!(history.get(&entries[0]), None);
+        history.record(&entries[0], 400);
+        assert_eq!(history.get(&entries[0]).map(|l| l.count), Some(1));
+        assert_eq!(history.launched(&entries).collect::<Vec<_>>(), vec![0, 1]);
+        assert!(history.remove("Play"));
+        assert!(history.is_empty())
+    }
+
+    #[test]
+    fn escaped_titles() {
+        let mut history = History::default();
+        history.record(&app("Two\nLines", "a"), 100);
+        history.record(&app("C:\\Games\\n64", "b\tc"), 200);
+        history.record(&app("Plain\\x", "c"), 300);
+        let source = history.to_string();
+        assert_eq!(source.lines().count(), 3);
+        assert_eq!(History::parse(&source), history);
+        assert_eq!(
+            History::parse("1\t0\tOld\\x\n").iter().next().unwrap().0,
+            "Old\\x"
+        )
+    }
+
+    #[test]
+    fn frecency() {
+        let week = 7 * 24 * 60 * 60;
+        let history = History::parse(&format!("4\t0\told\n1\t{week}\tnew\n"));
+        assert!((history.frecency(&app("old", ""), week) - 2.0).abs() < f64::EPSILON);
+        assert!((history.frecency(&app("new", ""), week) - 1.0).abs() < f64::EPSILON);
+        assert!(history.frecency(&app("never", ""), week).abs() < f64::EPSILON)
+    }
+}
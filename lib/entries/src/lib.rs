@@ -1,14 +1,41 @@
-use config::expand_user;
+use config::SortMode;
 use parser::parse_file;
-use std::{fs::read_dir, path::PathBuf, process::Command};
+use std::{
+    cmp::Reverse,
+    fs::read_dir,
+    mem::take,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
+mod conditions;
 mod errors;
+mod favorites;
+mod history;
 mod parse;
+mod style;
+pub use conditions::Conditions;
 pub use errors::Error;
-use parse::EntryBuilder;
+pub use favorites::Favorites;
+pub use history::{History, Launches, unix_time};
+use parse::{EntryFileBuilder, resolve_icon};
+pub use style::EntryStyle;
 
 pub const ICON_DIRS: [&str; 2] = ["/usr/share/pixmaps", "/usr/share/icons"];
 
+/// What selecting an entry does
+#[derive(Debug, Default)]
+pub enum EntryKind {
+    /// Run the launch command
+    #[default]
+    App,
+    /// Open a sub menu with the entries loaded from `path`
+    Folder {
+        path: PathBuf,
+        entries: Vec<MenuEntry>,
+    },
+}
+
 /// A Menu Entry
 #[derive(Debug)]
 pub struct MenuEntry {
@@ -20,46 +47,277 @@ pub struct MenuEntry {
     pub args: Vec<String>,
     /// Icon path to show in the ui
     pub icon: Option<PathBuf>,
+    /// Description shown next to the title in the list layout
+    pub description: Option<String>,
+    /// Category of the entry, entries of the same category are shown next to each other
+    pub category: Option<String>,
+    /// Position used by [`SortMode::Order`]
+    pub order: Option<i64>,
+    /// Whether the entry is pinned to the favorites row, see [`Favorites`]
+    pub favorite: bool,
+    /// Whether the entry is an app or a folder
+    pub kind: EntryKind,
+    /// Colors and sizes overriding the config
+    pub style: EntryStyle,
 }
 
 impl MenuEntry {
-    /// Load entry from a given file
+    /// Load the entries defined in a given file, in order of appearance
+    /// a file either defines a single entry or one entry per named section.
+    /// Entries whose [`Conditions`] are not met are skipped,
+    /// icons that cannot be found are reported and left out.
+    /// The entries of folders are loaded from their path, relative to the directory of the file,
+    /// folders whose path is not a directory are reported and skipped
     /// # Errors
-    /// Returns an error if the file could not be read or if the contents could not be parsed
-    pub fn from_file(path: PathBuf) -> Result<Self, Error> {
-        parse_file::<EntryBuilder>(path)
+    /// Returns an error if the file could not be read, if the contents could not be parsed,
+    /// if an entry is missing a required key or if a folder could not be loaded
+    pub fn from_file(path: PathBuf) -> Result<Vec<Self>, Error> {
+        Self::from_file_in(path, &[])
     }
 
     /// Try to load menu entries from given directories
     /// # Errors
     /// Returns an error if none of the directories could be loaded
-    pub fn load_dirs(dirs: &[&str]) -> Result<Vec<Self>, Error> {
+    pub fn load_dirs(dirs: &[PathBuf]) -> Result<Vec<Self>, Error> {
         let mut errs = vec![];
-        for dir in dirs {
-            let dir_path = expand_user(dir)?;
-            let res = Self::load_dir(&dir_path);
+        for dir_path in dirs {
+            let res = Self::load_dir(dir_path);
             match res {
                 Ok(slfs) => return Ok(slfs),
-                Err(err) => errs.push((dir_path, err)),
+                Err(err) => errs.push((dir_path.clone(), err)),
             }
         }
         Err(Error::NoEntriesFound { prev_errors: errs })
     }
 
     /// Load entries from a given directory
+    /// files are read in order of their names, see [`sort_entries`] to group them by category.
+    /// Each subdirectory is a folder titled by its name, unless a folder entry refers to it
     /// # Errors
-    /// Returns an error if files could not be read or if file contents could not be parsed
-    pub fn load_dir(path: &PathBuf) -> Result<Vec<Self>, Error> {
-        let mut entries = vec![];
+    /// Returns an error if files could not be read, if file contents could not be parsed
+    /// or if a folder contains itself
+    pub fn load_dir(path: &Path) -> Result<Vec<Self>, Error> {
+        Self::load_dir_in(path, &[])
+    }
+
+    /// A folder titled by the name of the directory `path`, with the entries in it
+    fn from_dir(path: PathBuf, ancestors: &[PathBuf]) -> Result<Self, Error> {
+        let entries = Self::load_dir_in(&path, ancestors)?;
+        Ok(Self {
+            title: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            launch: String::new(),
+            args: vec![],
+            icon: None,
+            description: None,
+            category: None,
+            order: None,
+            favorite: false,
+            kind: EntryKind::Folder { path, entries },
+            style: EntryStyle::default(),
+        })
+    }
+
+    /// Whether selecting the entry opens a sub menu
+    #[must_use]
+    pub const fn is_folder(&self) -> bool {
+        matches!(self.kind, EntryKind::Folder { .. })
+    }
+
+    /// [`Self::from_file`], where `ancestors` are the directories of the folders containing the file
+    fn from_file_in(path: PathBuf, ancestors: &[PathBuf]) -> Result<Vec<Self>, Error> {
+        let entries: Vec<Self> = parse_file::<EntryFileBuilder>(path.clone())?
+            .map_err(|(section, key)| -> Error {
+                parser::Error::missing_key(&path, &section, key, None).into()
+            })?
+            .into_iter()
+            .filter(|(_, conditions)| conditions.are_met())
+            .map(|(entry, _)| entry)
+            .collect();
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut loaded = Vec::with_capacity(entries.len());
+        for mut entry in entries {
+            if let Some(icon) = entry.icon.take() {
+                match resolve_icon(&icon) {
+                    Ok(icon) => entry.icon = Some(icon),
+                    Err(err) => eprintln!(
+                        "Skipping icon of {} in {}: {err}",
+                        entry.title,
+                        path.display()
+                    ),
+                }
+            }
+            if let Some(poster) = &mut entry.style.poster {
+                *poster = dir.join(&*poster);
+            }
+            if let EntryKind::Folder {
+                path: folder_path,
+                entries: folder_entries,
+            } = &mut entry.kind
+            {
+                *folder_path = dir.join(&*folder_path);
+                if !folder_path.is_dir() {
+                    eprintln!(
+                        "Skipping folder {} in {}, {} is not a directory",
+                        entry.title,
+                        path.display(),
+                        folder_path.display()
+                    );
+                    continue;
+                }
+                *folder_entries = Self::load_dir_in(folder_path, ancestors)?;
+            }
+            loaded.push(entry);
+        }
+        Ok(loaded)
+    }
+
+    /// [`Self::load_dir`], where `ancestors` are the directories of the folders containing `path`
+    fn load_dir_in(path: &Path, ancestors: &[PathBuf]) -> Result<Vec<Self>, Error> {
+        let dir = path
+            .canonicalize()
+            .map_err(|err| Error::read_dir(&err, path))?;
+        if ancestors.contains(&dir) {
+            return Err(Error::FolderCycle(path.to_path_buf()));
+        }
+        let ancestors = [ancestors, &[dir]].concat();
+        let mut paths = vec![];
         for path_entry in read_dir(path).map_err(|err| Error::read_dir(&err, path))? {
             let path_entry = path_entry.map_err(|err| Error::read_dir(&err, path))?;
-            let menu_entry = Self::from_file(path_entry.path())?;
-            entries.push(menu_entry);
+            if !path_entry.file_name().to_string_lossy().starts_with('.') {
+                paths.push(path_entry.path());
+            }
+        }
+        paths.sort();
+        let mut loaded = vec![];
+        for entry_path in paths {
+            let file_entries = if entry_path.is_dir() {
+                None
+            } else {
+                Some(Self::from_file_in(entry_path.clone(), &ancestors)?)
+            };
+            loaded.push((entry_path, file_entries));
+        }
+        // directories with a folder entry referring to them are only shown as that entry
+        let referenced: Vec<PathBuf> = loaded
+            .iter()
+            .flat_map(|(_, file_entries)| file_entries.iter().flatten())
+            .filter_map(|entry| match &entry.kind {
+                EntryKind::Folder { path, .. } => path.canonicalize().ok(),
+                EntryKind::App => None,
+            })
+            .collect();
+        let mut entries = vec![];
+        for (entry_path, file_entries) in loaded {
+            match file_entries {
+                Some(file_entries) => entries.extend(file_entries),
+                None if entry_path
+                    .canonicalize()
+                    .is_ok_and(|dir| referenced.contains(&dir)) => {}
+                None => entries.push(Self::from_dir(entry_path, &ancestors)?),
+            }
         }
         Ok(entries)
     }
 }
 
+#[cfg(test)]
+impl MenuEntry {
+    /// An app entry titled `title` without a command, for tests
+    pub(crate) fn test(title: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            launch: String::new(),
+            args: vec![],
+            icon: None,
+            description: None,
+            category: None,
+            order: None,
+            favorite: false,
+            kind: EntryKind::App,
+            style: EntryStyle::default(),
+        }
+    }
+}
+
+/// Move entries of the same category next to each other
+/// categories are ordered as given by `order`, followed by the remaining categories
+/// ordered by their first entry. The order inside a category is kept
+#[must_use]
+pub fn group_entries(entries: Vec<MenuEntry>, order: &[String]) -> Vec<MenuEntry> {
+    let mut groups: Vec<(Option<String>, Vec<MenuEntry>)> = vec![];
+    for entry in entries {
+        match groups
+            .iter_mut()
+            .find(|(group, _)| *group == entry.category)
+        {
+            Some((_, group_entries)) => group_entries.push(entry),
+            None => groups.push((entry.category.clone(), vec![entry])),
+        }
+    }
+    groups.sort_by_key(|(group, _)| {
+        group
+            .as_ref()
+            .and_then(|group| order.iter().position(|name| name == group))
+            .unwrap_or(order.len())
+    });
+    groups
+        .into_iter()
+        .flat_map(|(_, group_entries)| group_entries)
+        .collect()
+}
+
+/// Sort entries by `mode`, using `history` for the modes based on launches
+///
+/// The sort is stable. With `categories` entries of the same category are kept together,
+/// with categories ordered by `categories`, see [`group_entries`]
+#[must_use]
+pub fn sort_entries(
+    mut entries: Vec<MenuEntry>,
+    mode: SortMode,
+    history: &History,
+    categories: Option<&[String]>,
+) -> Vec<MenuEntry> {
+    match mode {
+        SortMode::Files => (),
+        SortMode::Alphabetical => entries.sort_by_cached_key(|entry| entry.title.to_lowercase()),
+        SortMode::Order => {
+            entries.sort_by_key(|entry| (entry.order.is_none(), entry.order.unwrap_or_default()));
+        }
+        SortMode::Recent => {
+            entries.sort_by_cached_key(|entry| Reverse(history.get(entry).unwrap_or_default().last))
+        }
+        SortMode::Frequent => entries.sort_by_cached_key(|entry| {
+            let launches = history.get(entry).unwrap_or_default();
+            Reverse((launches.count, launches.last))
+        }),
+        SortMode::Frecency => {
+            let time = unix_time();
+            let mut scored: Vec<(f64, MenuEntry)> = entries
+                .into_iter()
+                .map(|entry| (history.frecency(&entry, time), entry))
+                .collect();
+            scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+            entries = scored.into_iter().map(|(_, entry)| entry).collect();
+        }
+    }
+    for entry in &mut entries {
+        if let EntryKind::Folder {
+            entries: folder_entries,
+            ..
+        } = &mut entry.kind
+        {
+            *folder_entries = sort_entries(take(folder_entries), mode, history, categories);
+        }
+    }
+    match categories {
+        Some(categories) => group_entries(entries, categories),
+        None => entries,
+    }
+}
+
 #[must_use]
 pub fn launch_command(cmd: &str, args: &[String]) -> Command {
     let mut cmd = Command::new(cmd);
@@ -68,3 +326,163 @@ pub fn launch_command(cmd: &str, args: &[String]) -> Command {
     }
     cmd
 }
+
+#[cfg(test)]
+mod sort_tests {
+    use super::{History, MenuEntry, group_entries, sort_entries};
+    use config::SortMode;
+
+    fn entry(title: &str, category: Option<&str>) -> MenuEntry {
+        MenuEntry {
+            category: category.map(str::to_owned),
+            ..MenuEntry::test(title)
+        }
+    }
+
+    fn titles(entries: Vec<MenuEntry>) -> Vec<String> {
+        entries.into_iter().map(|entry| entry.title).collect()
+    }
+
+    #[test]
+    fn groups_kept_together() {
+        let entries = vec![
+            entry("a", Some("Games")),
+            entry("b", None),
+            entry("c", Some("Games")),
+            entry("d", None),
+        ];
+        assert_eq!(
+            titles(group_entries(entries, &[])),
+            vec!["a", "c", "b", "d"]
+        )
+    }
+
+    #[test]
+    fn category_order() {
+        let entries = vec![
+            entry("a", Some("Tools")),
+            entry("b", None),
+            entry("c", Some("Games")),
+            entry("d", Some("Media")),
+        ];
+        let order = ["Media".to_owned(), "Games".to_owned()];
+        assert_eq!(
+            titles(group_entries(entries, &order)),
+            vec!["d", "c", "a", "b"]
+        )
+    }
+
+    #[test]
+    fn sort_modes() {
+        let history = History::parse("1\t300\tB\n5\t100\tc\n");
+        let entries = || {
+            let mut entries = vec![entry("c", None), entry("B", None), entry("a", None)];
+            entries[0].order = Some(2);
+            entries[2].order = Some(1);
+            entries
+        };
+        let sort = |mode| titles(sort_entries(entries(), mode, &history, None));
+        assert_eq!(sort(SortMode::Files), vec!["c", "B", "a"]);
+        assert_eq!(sort(SortMode::Alphabetical), vec!["a", "B", "c"]);
+        assert_eq!(sort(SortMode::Order), vec!["a", "c", "B"]);
+        assert_eq!(sort(SortMode::Frequent), vec!["c", "B", "a"]);
+        assert_eq!(sort(SortMode::Recent), vec!["B", "c", "a"])
+    }
+
+    #[test]
+    fn grouped_only_with_categories() {
+        let entries = || {
+            vec![
+                entry("c", Some("Games")),
+                entry("b", None),
+                entry("a", Some("Games")),
+            ]
+        };
+        let history = History::default();
+        let sort = |categories| {
+            titles(sort_entries(
+                entries(),
+                SortMode::Files,
+                &history,
+                categories,
+            ))
+        };
+        assert_eq!(sort(None), vec!["c", "b", "a"]);
+        assert_eq!(sort(Some(&[])), vec!["c", "a", "b"])
+    }
+}
+
+#[cfg(test)]
+mod folder_tests {
+    use super::{EntryKind, Error, MenuEntry};
+    use std::fs::{create_dir_all, write};
+    use tempfile::TempDir;
+
+    fn setup(files: &[(&str, &str)]) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in files {
+            let path = dir.path().join(path);
+            create_dir_all(path.parent().unwrap()).unwrap();
+            write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn load_dir() {
+        let dir = setup(&[
+            ("a.conf", "title = A\nlaunch = a"),
+            ("b.conf", "title = B\nlaunch = b\nhidden = true"),
+            ("Emulators/snes.conf", "title = SNES\nlaunch = snes"),
+            ("menu.conf", "title = Tools\ntype = folder\npath = other"),
+            ("other/x.conf", "title = X\nlaunch = x"),
+        ]);
+        let entries = MenuEntry::load_dir(dir.path()).unwrap();
+        let titles: Vec<&str> = entries.iter().map(|entry| entry.title.as_str()).collect();
+        assert_eq!(titles, vec!["Emulators", "A", "Tools"]);
+        let EntryKind::Folder { entries, .. } = &entries[2].kind else {
+            panic!("Tools is not a folder")
+        };
+        assert_eq!(entries[0].title, "X")
+    }
+
+    #[test]
+    fn missing_folder() {
+        let dir = setup(&[
+            ("a.conf", "title = A\nlaunch = a"),
+            ("menu.conf", "title = Tools\ntype = folder\npath = missing"),
+        ]);
+        let entries = MenuEntry::load_dir(dir.path()).unwrap();
+        let titles: Vec<&str> = entries.iter().map(|entry| entry.title.as_str()).collect();
+        assert_eq!(titles, vec!["A"])
+    }
+
+    #[test]
+    fn icons_after_conditions() {
+        let icon = "tvmenu-test-missing-icon";
+        let dir = setup(&[
+            (
+                "kodi.conf",
+                &format!(
+                    "title = Kodi\nlaunch = kodi\ntry-exec = tvmenu-test-missing\nicon = {icon}"
+                ),
+            ),
+            (
+                "web.conf",
+                &format!("title = Web\nlaunch = web\nhidden = yes\nicon = {icon}"),
+            ),
+            ("a.conf", &format!("title = A\nlaunch = a\nicon = {icon}")),
+        ]);
+        let entries = MenuEntry::load_dir(dir.path()).unwrap();
+        let titles: Vec<&str> = entries.iter().map(|entry| entry.title.as_str()).collect();
+        assert_eq!(titles, vec!["A"]);
+        assert_eq!(entries[0].icon, None)
+    }
+
+    #[test]
+    fn cycle() {
+        let dir = setup(&[("loop.conf", "title = Loop\ntype = folder\npath = .")]);
+        let result = MenuEntry::load_dir(dir.path());
+        assert!(matches!(result, Err(Error::FolderCycle(_))))
+    }
+}
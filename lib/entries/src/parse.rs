@@ -1,71 +1,266 @@
-use crate::{Error, ICON_DIRS, MenuEntry};
-use parser::{ConfigBuilder, Key, Section};
-use std::{ffi::OsStr, fs::read_dir, path::PathBuf};
+use crate::{Conditions, EntryKind, EntryStyle, Error, ICON_DIRS, MenuEntry};
+use parser::{ConfigBuilder, FromConfigValue, Key, Section};
+use std::{
+    ffi::OsStr,
+    fs::read_dir,
+    path::{Path, PathBuf},
+};
 
-#[derive(Default)]
-pub struct EntryBuilder {
+/// Value of the `type` key
+#[derive(FromConfigValue, Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryType {
+    App,
+    Folder,
+}
+
+/// Values of a single entry, either from a named section or the empty section of a file
+#[derive(Default, Clone)]
+struct PartialEntry {
     title: Option<String>,
     launch: Option<String>,
     args: Vec<String>,
     icon: Option<PathBuf>,
+    description: Option<String>,
+    category: Option<String>,
+    order: Option<i64>,
+    favorite: Option<bool>,
+    kind: Option<EntryType>,
+    path: Option<PathBuf>,
+    conditions: Conditions,
+    style: EntryStyle,
 }
 
-impl ConfigBuilder for EntryBuilder {
-    type Output = MenuEntry;
+/// A key that is required but missing, given by section and key
+pub type MissingKey = (String, &'static str);
+
+/// Builder for the entries of a file
+///
+/// A file without named sections defines a single entry.
+/// Otherwise each named section (e.g. `[Firefox]`) defines an entry, titled by the section name
+/// unless it sets `title`, and the keys of the empty section are used for keys an entry leaves out.
+#[derive(Default)]
+pub struct EntryFileBuilder {
+    /// values of the empty section
+    defaults: PartialEntry,
+    /// entries in named sections, in order of appearance
+    entries: Vec<(String, PartialEntry)>,
+}
+
+impl PartialEntry {
+    fn parse_value(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        match key.trim() {
+            "title" => self.title = Some(String::from_config_value(value)?),
+            "launch" => {
+                let launch = String::from_config_value(value)?;
+                let mut parts = split_command(&launch)?.into_iter();
+                self.launch = parts.next();
+                self.args = parts.collect();
+            }
+            // resolved by `resolve_icon` once the conditions of the entry are met
+            "icon" => self.icon = Some(PathBuf::from_config_value(value)?),
+            "description" | "comment" => {
+                self.description = Some(String::from_config_value(value)?);
+            }
+            "category" | "group" => self.category = Some(String::from_config_value(value)?),
+            "categories" => {
+                // freedesktop style list, e.g. `Game;ArcadeGame;`, the first one is used
+                // unless `category` is given
+                let categories = String::from_config_value(value)?;
+                let first = categories
+                    .split(';')
+                    .map(str::trim)
+                    .find(|cat| !cat.is_empty());
+                if self.category.is_none() {
+                    self.category = first.map(str::to_owned);
+                }
+            }
+            "order" => self.order = Some(i64::from_config_value(value)?),
+            "favorite" => self.favorite = Some(bool::from_config_value(value)?),
+            "type" => self.kind = Some(EntryType::from_config_value(value)?),
+            "path" => self.path = Some(PathBuf::from_config_value(value)?),
+            "hidden" => self.conditions.hidden = Some(bool::from_config_value(value)?),
+            "only-if-exists" => {
+                self.conditions.only_if_exists = Some(PathBuf::from_config_value(value)?);
+            }
+            "try-exec" => self.conditions.try_exec = Some(String::from_config_value(value)?),
+            "only-on-host" => {
+                self.conditions.only_on_host = Some(Vec::from_config_value(value)?);
+            }
+            key if self.style.parse_value(key, value)? => (),
+            _ => return Err(Error::UnknownKey(key.to_owned())),
+        }
+        Ok(())
+    }
+
+    /// Build the entry and its conditions, taking missing values from `defaults`
+    /// the entries of a folder are left empty, its path is not resolved
+    fn build(self, section: &str, defaults: &Self) -> Result<(MenuEntry, Conditions), MissingKey> {
+        let title = self
+            .title
+            .or_else(|| (!section.is_empty()).then(|| section.to_owned()))
+            .ok_or_else(|| (section.to_owned(), "title"))?;
+        let kind = match self.kind.or(defaults.kind) {
+            Some(EntryType::Folder) => EntryKind::Folder {
+                path: self.path.ok_or_else(|| (section.to_owned(), "path"))?,
+                entries: vec![],
+            },
+            Some(EntryType::App) | None => EntryKind::App,
+        };
+        let (launch, args) = match self.launch {
+            Some(launch) => (launch, self.args),
+            None if matches!(kind, EntryKind::Folder { .. }) => (String::new(), vec![]),
+            None => (
+                defaults
+                    .launch
+                    .clone()
+                    .ok_or_else(|| (section.to_owned(), "launch"))?,
+                defaults.args.clone(),
+            ),
+        };
+        let entry = MenuEntry {
+            title,
+            launch,
+            args,
+            icon: self.icon.or_else(|| defaults.icon.clone()),
+            description: self.description.or_else(|| defaults.description.clone()),
+            category: self.category.or_else(|| defaults.category.clone()),
+            order: self.order.or(defaults.order),
+            favorite: self.favorite.or(defaults.favorite).unwrap_or_default(),
+            kind,
+            style: self.style.or(&defaults.style),
+        };
+        Ok((entry, self.conditions.or(&defaults.conditions)))
+    }
+}
+
+impl ConfigBuilder for EntryFileBuilder {
+    type Output = Result<Vec<(MenuEntry, Conditions)>, MissingKey>;
     type Error = Error;
 
     fn sections() -> Vec<Section> {
-        vec![Section::empty()]
+        vec![Section::new("", true), Section::any()]
+    }
+
+    fn section_keys(_: &str) -> Result<Vec<Key>, Self::Error> {
+        let keys = [
+            "title",
+            "launch",
+            "icon",
+            "description",
+            "comment",
+            "category",
+            "group",
+            "categories",
+            "order",
+            "favorite",
+            "type",
+            "path",
+            "hidden",
+            "only-if-exists",
+            "try-exec",
+            "only-on-host",
+        ];
+        Ok(keys
+            .into_iter()
+            .chain(EntryStyle::KEYS)
+            .map(|key| Key::new(key, true))
+            .collect())
     }
 
-    fn section_keys(section: &str) -> Result<Vec<Key>, Self::Error> {
+    fn parse_value(&mut self, section: &str, key: &str, value: &str) -> Result<(), Self::Error> {
         if section.is_empty() {
-            Ok(vec![
-                Key::new("title", false),
-                Key::new("launch", false),
-                Key::new("icon", true),
-            ])
-        } else {
-            Err(Error::UnknownSection(section.to_owned()))
+            return self.defaults.parse_value(key, value);
         }
+        let entry = match self.entries.iter().position(|(name, _)| name == section) {
+            Some(ind) => &mut self.entries[ind].1,
+            None => {
+                self.entries
+                    .push((section.to_owned(), PartialEntry::default()));
+                &mut self.entries.last_mut().expect("entry was just added").1
+            }
+        };
+        entry.parse_value(key, value)
     }
 
-    fn parse_value(&mut self, section: &str, key: &str, value: &str) -> Result<(), Self::Error> {
-        if !section.is_empty() {
-            return Err(Error::UnknownSection(section.to_owned()));
+    fn build(self) -> Self::Output {
+        if self.entries.is_empty() {
+            let entry = self.defaults.build("", &PartialEntry::default())?;
+            return Ok(vec![entry]);
         }
+        self.entries
+            .into_iter()
+            .map(|(section, entry)| entry.build(&section, &self.defaults))
+            .collect()
+    }
+}
 
-        match key.trim() {
-            "title" => self.title = Some(value.to_owned()),
-            "launch" => {
-                for part in value.split(' ') {
-                    if self.launch.is_none() {
-                        self.launch = Some(part.to_owned());
-                    } else {
-                        self.args.push(part.to_owned());
+/// Split a command into words like a shell does
+/// words are separated by whitespace, `'...'` is taken literally,
+/// inside `"..."` and outside of quotes `\\` escapes the next character
+fn split_command(command: &str) -> Result<Vec<String>, Error> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => word.push(ch),
+                        None => return Err(Error::UnterminatedQuote(command.to_owned())),
                     }
                 }
             }
-            "icon" => self.icon = Some(find_icon(value)?),
-            _ => return Err(Error::UnknownKey(key.to_owned())),
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(esc @ ('"' | '\\')) => word.push(esc),
+                            Some(other) => {
+                                word.push('\\');
+                                word.push(other);
+                            }
+                            None => return Err(Error::UnterminatedQuote(command.to_owned())),
+                        },
+                        Some(ch) => word.push(ch),
+                        None => return Err(Error::UnterminatedQuote(command.to_owned())),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_default().extend(chars.next()),
+            ch => word.get_or_insert_default().push(ch),
         }
-        Ok(())
     }
+    words.extend(word);
+    Ok(words)
+}
 
-    fn build(self) -> Self::Output {
-        MenuEntry {
-            title: self.title.unwrap(),
-            launch: self.launch.unwrap(),
-            args: self.args,
-            icon: self.icon,
-        }
+/// Path of the icon given by the `icon` key
+/// an existing absolute path is used as is, otherwise the icon is searched by name
+/// # Errors
+/// Returns an error if the icon could not be found or an icon directory could not be read
+pub fn resolve_icon(icon: &Path) -> Result<PathBuf, Error> {
+    if icon.is_absolute() && icon.exists() {
+        Ok(icon.to_path_buf())
+    } else {
+        find_icon(&icon.to_string_lossy())
     }
 }
 
+/// The largest icon called `name` in [`ICON_DIRS`], directories that do not exist are skipped
 fn find_icon(name: &str) -> Result<PathBuf, Error> {
     let mut icons = vec![];
-    for dir in ICON_DIRS {
-        icons.extend(find_icon_dir(name, &PathBuf::from(&dir))?);
+    for dir in ICON_DIRS
+        .map(PathBuf::from)
+        .iter()
+        .filter(|dir| dir.is_dir())
+    {
+        icons.extend(find_icon_dir(name, dir)?);
     }
     let icon_path = icons
         .iter()
@@ -97,3 +292,162 @@ fn find_icon_dir(name: &str, dir: &PathBuf) -> Result<Vec<PathBuf>, Error> {
     }
     Ok(icons)
 }
+
+#[cfg(test)]
+mod entry_file_tests {
+    use super::EntryFileBuilder;
+    use crate::{EntryKind, MenuEntry};
+    use config::Size;
+    use parser::ConfigBuilder;
+
+    fn build(values: &[(&str, &str, &str)]) -> Vec<MenuEntry> {
+        let mut builder = EntryFileBuilder::default();
+        for (section, key, value) in values {
+            builder.parse_value(section, key, value).unwrap();
+        }
+        builder
+            .build()
+            .unwrap()
+            .into_iter()
+            .map(|(entry, _)| entry)
+            .collect()
+    }
+
+    #[test]
+    fn single_entry() {
+        let result = build(&[
+            ("", "title", "Kodi"),
+            ("", "launch", "kodi --fs"),
+            ("", "comment", "Media center"),
+        ]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].title, "Kodi");
+        assert_eq!(result[0].launch, "kodi");
+        assert_eq!(result[0].args, vec!["--fs"]);
+        assert_eq!(result[0].description.as_deref(), Some("Media center"))
+    }
+
+    #[test]
+    fn quoted_arguments() {
+        let result = build(&[
+            ("A", "launch", "sh  -c \"echo hi\""),
+            (
+                "B",
+                "launch",
+                "mpv 'My Video.mkv' a\\ b \"say \\\"hi\\\"\" ''",
+            ),
+        ]);
+        assert_eq!(result[0].launch, "sh");
+        assert_eq!(result[0].args, vec!["-c", "echo hi"]);
+        assert_eq!(
+            result[1].args,
+            vec!["My Video.mkv", "a b", "say \"hi\"", ""]
+        );
+        let mut builder = EntryFileBuilder::default();
+        assert!(builder.parse_value("", "launch", "sh -c 'open").is_err())
+    }
+
+    #[test]
+    fn named_sections() {
+        let result = build(&[
+            ("", "category", "Apps"),
+            ("Kodi", "launch", "kodi"),
+            ("Firefox", "launch", "firefox"),
+            ("Firefox", "title", "Web"),
+        ]);
+        let titles: Vec<&str> = result.iter().map(|entry| entry.title.as_str()).collect();
+        assert_eq!(titles, vec!["Kodi", "Web"]);
+        assert!(
+            result
+                .iter()
+                .all(|entry| entry.category.as_deref() == Some("Apps"))
+        )
+    }
+
+    #[test]
+    fn freedesktop_categories() {
+        let result = build(&[
+            ("Kodi", "launch", "kodi"),
+            ("Kodi", "categories", "AudioVideo;Video;"),
+            ("Game", "launch", "game"),
+            ("Game", "category", "Games"),
+            ("Game", "categories", "Game;"),
+        ]);
+        assert_eq!(result[0].category.as_deref(), Some("AudioVideo"));
+        assert_eq!(result[1].category.as_deref(), Some("Games"))
+    }
+
+    #[test]
+    fn group_alias() {
+        let result = build(&[
+            ("", "title", "Kodi"),
+            ("", "launch", "kodi"),
+            ("", "group", "Media"),
+        ]);
+        assert_eq!(result[0].category.as_deref(), Some("Media"))
+    }
+
+    #[test]
+    fn folder() {
+        let result = build(&[
+            ("Emulators", "type", "folder"),
+            ("Emulators", "path", "emu"),
+        ]);
+        assert!(matches!(
+            &result[0].kind,
+            EntryKind::Folder { path, .. } if path.as_os_str() == "emu"
+        ));
+        let mut builder = EntryFileBuilder::default();
+        builder.parse_value("Emulators", "type", "folder").unwrap();
+        assert_eq!(
+            builder.build().err(),
+            Some(("Emulators".to_owned(), "path"))
+        )
+    }
+
+    #[test]
+    fn conditions() {
+        let mut builder = EntryFileBuilder::default();
+        for (section, key, value) in [
+            ("", "only-on-host", "tv, desktop"),
+            ("Kodi", "launch", "kodi"),
+            ("Kodi", "try-exec", "kodi"),
+            ("Web", "launch", "firefox"),
+            ("Web", "hidden", "yes"),
+        ] {
+            builder.parse_value(section, key, value).unwrap();
+        }
+        let result = builder.build().unwrap();
+        let hosts = Some(vec!["tv".to_owned(), "desktop".to_owned()]);
+        assert_eq!(result[0].1.try_exec.as_deref(), Some("kodi"));
+        assert_eq!(result[0].1.only_on_host, hosts);
+        assert_eq!(result[1].1.hidden, Some(true));
+        assert_eq!(result[1].1.only_on_host, hosts)
+    }
+
+    #[test]
+    fn style() {
+        let result = build(&[
+            ("", "background", "#ff0000"),
+            ("Kodi", "launch", "kodi"),
+            ("Kodi", "width", "20%"),
+            ("Kodi", "poster", "kodi.png"),
+        ]);
+        let style = &result[0].style;
+        assert_eq!(style.background.map(|color| color.red), Some(255));
+        assert_eq!(style.width, Some(Size::Percent(20.0)));
+        assert_eq!(style.height, None);
+        assert_eq!(
+            style.poster.as_deref(),
+            Some(std::path::Path::new("kodi.png"))
+        )
+    }
+
+    #[test]
+    fn missing_launch() {
+        let mut builder = EntryFileBuilder::default();
+        builder.parse_value("Kodi", "title", "Kodi").unwrap();
+        let result = builder.build();
+        assert_eq!(result.err(), Some(("Kodi".to_owned(), "launch")))
+    }
+}
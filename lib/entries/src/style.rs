@@ -0,0 +1,72 @@
+use crate::Error;
+use config::{Color, Size};
+use parser::FromConfigValue;
+use std::path::PathBuf;
+
+/// Style of a single entry, overriding the values of the `[Entries]` section of the config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntryStyle {
+    /// `background`
+    pub background: Option<Color>,
+    /// `background-active`
+    pub background_active: Option<Color>,
+    /// `text-color`
+    pub text_color: Option<Color>,
+    /// `text-size`
+    pub text_size: Option<Size>,
+    /// `border-radius`
+    pub border_radius: Option<Size>,
+    /// `width`
+    pub width: Option<Size>,
+    /// `height`
+    pub height: Option<Size>,
+    /// `poster`, an image filling the background of the entry
+    pub poster: Option<PathBuf>,
+}
+
+impl EntryStyle {
+    /// Keys of the style
+    pub const KEYS: [&str; 8] = [
+        "background",
+        "background-active",
+        "text-color",
+        "text-size",
+        "border-radius",
+        "width",
+        "height",
+        "poster",
+    ];
+
+    /// Parse the value of a style key, returns `false` if `key` is not a style key
+    /// # Errors
+    /// Returns an error if the value cannot be parsed
+    pub fn parse_value(&mut self, key: &str, value: &str) -> Result<bool, Error> {
+        match key {
+            "background" => self.background = Some(Color::from_config_value(value)?),
+            "background-active" => self.background_active = Some(Color::from_config_value(value)?),
+            "text-color" => self.text_color = Some(Color::from_config_value(value)?),
+            "text-size" => self.text_size = Some(Size::from_config_value(value)?),
+            "border-radius" => self.border_radius = Some(Size::from_config_value(value)?),
+            "width" => self.width = Some(Size::from_config_value(value)?),
+            "height" => self.height = Some(Size::from_config_value(value)?),
+            "poster" => self.poster = Some(PathBuf::from_config_value(value)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Take values that are not set from `defaults`
+    #[must_use]
+    pub fn or(self, defaults: &Self) -> Self {
+        Self {
+            background: self.background.or(defaults.background),
+            background_active: self.background_active.or(defaults.background_active),
+            text_color: self.text_color.or(defaults.text_color),
+            text_size: self.text_size.or(defaults.text_size),
+            border_radius: self.border_radius.or(defaults.border_radius),
+            width: self.width.or(defaults.width),
+            height: self.height.or(defaults.height),
+            poster: self.poster.or_else(|| defaults.poster.clone()),
+        }
+    }
+}
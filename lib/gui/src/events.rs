@@ -1,21 +1,37 @@
 use crate::{MenuState, Message};
 use entries::launch_command;
-use iced::keyboard::{Key, key::Named};
+use iced::keyboard::{Key, Modifiers, key::Named};
 use std::process::{Command, exit};
 
-fn launch_entry(cmd: &str, args: &[String]) {
-    match launch_command(cmd, args).spawn() {
-        Ok(child) => std::mem::forget(child),
-        Err(err) => eprintln!("Could not launch {cmd}:\n{err}"),
+/// Launch the entry with index `ind` and record the launch in the history
+/// folders are opened instead
+fn launch_entry(state: &mut MenuState, ind: usize) {
+    let Some(entry) = state.entries.get(ind) else {
+        return;
+    };
+    if entry.is_folder() {
+        state.open_folder(ind);
+        return;
+    }
+    match launch_command(&entry.launch, &entry.args).spawn() {
+        Ok(child) => {
+            std::mem::forget(child);
+            state.record_launch(ind);
+        }
+        Err(err) => eprintln!("Could not launch {}:\n{err}", entry.launch),
     }
 }
 
 pub fn update(state: &mut MenuState, msg: Message) {
     match msg {
-        Message::Launch(cmd, args) => launch_entry(&cmd, &args),
-        Message::Resized { height, width } => state.window_size = (width, height),
-        Message::KeyPress(key) => handle_key(state, &key),
+        Message::Launch(ind) => launch_entry(state, ind),
+        Message::Resized { height, width } => state.resize(width, height),
+        Message::KeyPress(key, modifiers) => handle_key(state, &key, modifiers),
         Message::FilterChanged(filter) => update_filter(state, &filter),
+        Message::SelectCategory(ind) => state.selected_category = ind,
+        Message::Back => {
+            state.close_folder();
+        }
         Message::Exit => exit(0),
         Message::Lock => {
             Command::new("loginctl")
@@ -38,45 +54,63 @@ pub fn update(state: &mut MenuState, msg: Message) {
     }
 }
 
-fn handle_key(state: &mut MenuState, key: &Key) {
-    match key {
-        Key::Named(Named::ArrowDown) => {
-            let widgets_per_col = state.widgets_per_col() as usize;
-            let new_ind = state.selected_index + widgets_per_col;
-            if new_ind < state.entries.len() - 1 {
-                state.selected_index = new_ind;
-            } else {
-                state.selected_index = state.entries.len() - 1;
-            }
+fn handle_key(state: &mut MenuState, key: &Key, modifiers: Modifiers) {
+    let Key::Named(named) = key else {
+        return;
+    };
+    match named {
+        Named::ArrowUp | Named::ArrowDown | Named::ArrowLeft | Named::ArrowRight => {
+            move_selection(state, *named);
         }
-        Key::Named(Named::ArrowUp) => {
-            let widgets_per_col = state.widgets_per_col() as usize;
-            if widgets_per_col > state.selected_index {
-                state.selected_index = 0;
-            } else {
-                state.selected_index -= widgets_per_col;
-            }
+        Named::Tab if state.shows_tabs() && modifiers.shift() => {
+            state.previous_category();
         }
-        Key::Named(Named::ArrowLeft) => {
-            if state.selected_index > 0 {
-                state.selected_index -= 1;
-            }
+        Named::Tab | Named::PageDown if state.shows_tabs() => {
+            state.next_category();
         }
-        Key::Named(Named::ArrowRight) => {
-            if state.selected_index < state.entries.len() - 1 {
-                state.selected_index += 1;
+        Named::PageUp if state.shows_tabs() => {
+            state.previous_category();
+        }
+        Named::Enter => {
+            if let Some(ind) = state.selected_entry() {
+                launch_entry(state, ind);
             }
         }
-        Key::Named(Named::Enter) => {
-            let selected = &state.entries[state.selected_index];
-            launch_entry(&selected.launch, &selected.args);
+        Named::Escape | Named::GoBack | Named::BrowserBack => {
+            state.close_folder();
+        }
+        Named::ContextMenu | Named::BrowserFavorites => {
+            if let Some(ind) = state.selected_entry() {
+                state.toggle_favorite(ind);
+            }
         }
         _ => (),
     }
 }
 
+/// Move the selection in the direction of an arrow key
+/// moving left or right past the end of a row continues in the previous or next row
+fn move_selection(state: &mut MenuState, key: Named) {
+    let rows = state.rows();
+    let Some((row, col)) = state.selected_position(&rows) else {
+        return;
+    };
+    let (row, col) = match key {
+        Named::ArrowUp if row > 0 => (row - 1, col),
+        Named::ArrowDown if row + 1 < rows.len() => (row + 1, col),
+        Named::ArrowLeft if col > 0 => (row, col - 1),
+        Named::ArrowLeft if row > 0 => (row - 1, usize::MAX),
+        Named::ArrowRight if col + 1 < rows[row].entries.len() => (row, col + 1),
+        Named::ArrowRight if row + 1 < rows.len() => (row + 1, 0),
+        _ => return,
+    };
+    let col = col.min(rows[row].entries.len() - 1);
+    state.select(&rows, row, col);
+}
+
 fn update_filter(state: &mut MenuState, filter_value: &str) {
     filter_value.clone_into(&mut state.filter_value);
+    state.selected_top = None;
     state.disabled_indices.clear();
     for (ind, entry) in state.entries.iter().enumerate() {
         if !match_strings(&entry.title, filter_value) {
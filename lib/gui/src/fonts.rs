@@ -0,0 +1,160 @@
+use config::{AppConfig, FontWeight};
+use fontdb::Database;
+use iced::{
+    Font,
+    font::{Family, Weight},
+};
+use std::{fs::read, path::Path};
+
+/// Fonts of the window and the entries, with the data of the font files to load
+#[derive(Debug, Default)]
+pub struct Fonts {
+    /// font of the window
+    pub window: Font,
+    /// font of the entries
+    pub entries: Font,
+    /// contents of the font files
+    pub data: Vec<Vec<u8>>,
+}
+
+impl Fonts {
+    /// Load the font files and find the fonts set in `config`
+    /// fonts that cannot be loaded or found are reported and replaced by the default font
+    pub fn load(config: &AppConfig) -> Self {
+        let mut fonts = Self::default();
+        let mut system = None;
+        let window_family = fonts.family(
+            config.font.as_deref(),
+            config.font_file.as_deref(),
+            &mut system,
+        );
+        let entry_family = fonts
+            .family(
+                config.entries.font.as_deref(),
+                config.entries.font_file.as_deref(),
+                &mut system,
+            )
+            .or(window_family);
+        fonts.window = font(window_family, config.font_weight);
+        fonts.entries = font(entry_family, config.entries.font_weight);
+        fonts
+    }
+
+    /// Family named `name` or the family of `file`, `None` for the default font
+    /// the installed fonts are only loaded into `system` if `name` is not found in `file`
+    fn family(
+        &mut self,
+        name: Option<&str>,
+        file: Option<&Path>,
+        system: &mut Option<Database>,
+    ) -> Option<&'static str> {
+        let mut file_db = Database::new();
+        if let Some(file) = file {
+            match read(file) {
+                Ok(data) => {
+                    file_db.load_font_data(data.clone());
+                    if file_db.is_empty() {
+                        eprintln!(
+                            "{} is not a font file, using the default font",
+                            file.display()
+                        );
+                    } else {
+                        self.data.push(data);
+                    }
+                }
+                Err(err) => eprintln!(
+                    "Could not load font file {}, using the default font:\n{err}",
+                    file.display()
+                ),
+            }
+        }
+        let name = match name {
+            Some(name) => find_family(&file_db, name).or_else(|| {
+                let system = system.get_or_insert_with(|| {
+                    let mut db = Database::new();
+                    db.load_system_fonts();
+                    db
+                });
+                let family = find_family(system, name);
+                if family.is_none() {
+                    eprintln!("Could not find font {name}, using the default font");
+                }
+                family
+            })?,
+            None => first_family(&file_db)?,
+        };
+        // iced refers to font families by static names, they are only created once at startup
+        Some(Box::leak(name.into_boxed_str()))
+    }
+}
+
+/// Font of `family` with `weight`, the default font if `family` is not set
+fn font(family: Option<&'static str>, weight: FontWeight) -> Font {
+    Font {
+        family: family.map_or(Font::DEFAULT.family, Family::Name),
+        weight: to_weight(weight),
+        ..Font::DEFAULT
+    }
+}
+
+/// Family of the first font in `db`
+fn first_family(db: &Database) -> Option<String> {
+    db.faces()
+        .find_map(|face| face.families.first())
+        .map(|(name, _)| name.clone())
+}
+
+/// Family of a font in `db` named `name` ignoring case, as it is named in the font
+fn find_family(db: &Database, name: &str) -> Option<String> {
+    db.faces()
+        .flat_map(|face| &face.families)
+        .find(|(family, _)| family.eq_ignore_ascii_case(name))
+        .map(|(family, _)| family.clone())
+}
+
+/// convert [`FontWeight`] to [`Weight`]
+const fn to_weight(weight: FontWeight) -> Weight {
+    match weight {
+        FontWeight::Thin => Weight::Thin,
+        FontWeight::ExtraLight => Weight::ExtraLight,
+        FontWeight::Light => Weight::Light,
+        FontWeight::Normal => Weight::Normal,
+        FontWeight::Medium => Weight::Medium,
+        FontWeight::Semibold => Weight::Semibold,
+        FontWeight::Bold => Weight::Bold,
+        FontWeight::ExtraBold => Weight::ExtraBold,
+        FontWeight::Black => Weight::Black,
+    }
+}
+
+#[cfg(test)]
+mod fonts_tests {
+    use super::Fonts;
+    use config::{AppConfig, FontWeight};
+    use iced::{Font, font::Weight};
+    use std::{fs::write, path::PathBuf};
+
+    #[test]
+    fn fallback() {
+        let fonts = Fonts::load(&AppConfig::default());
+        assert_eq!(fonts.window, Font::DEFAULT);
+        assert_eq!(fonts.entries, Font::DEFAULT);
+        let mut config = AppConfig {
+            font_file: Some(PathBuf::from("/does/not/exist.ttf")),
+            ..AppConfig::default()
+        };
+        config.entries.font_weight = FontWeight::Bold;
+        let fonts = Fonts::load(&config);
+        assert!(fonts.data.is_empty());
+        assert_eq!(fonts.window, Font::DEFAULT);
+        assert_eq!(fonts.entries.family, Font::DEFAULT.family);
+        assert_eq!(fonts.entries.weight, Weight::Bold);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("font.ttf");
+        write(&path, "not a font").unwrap();
+        config.font_file = Some(path);
+        let fonts = Fonts::load(&config);
+        assert!(fonts.data.is_empty());
+        assert_eq!(fonts.window, Font::DEFAULT)
+    }
+}
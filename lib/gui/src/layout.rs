@@ -0,0 +1,96 @@
+/// Width available to the menu in a window `window_width` wide
+/// the padding is applied on both sides, once around the window and once around the menu
+pub const fn menu_width(window_width: f32, padding: f32) -> f32 {
+    padding.mul_add(-4.0, window_width).max(0.0)
+}
+
+/// Number of columns of `entry_width` separated by `gap` fitting into `available` width,
+/// at least one
+pub fn columns(available: f32, entry_width: f32, gap: f32) -> usize {
+    (((available + gap) / (entry_width + gap)).floor() as usize).max(1)
+}
+
+/// Width of a row of entries `widths` wide separated by `gap`
+pub fn row_width(widths: &[f32], gap: f32) -> f32 {
+    (widths.iter().map(|width| width + gap).sum::<f32>() - gap).max(0.0)
+}
+
+/// Rows of the menu grid, filled with as many entries as fit into the available width
+/// entries can differ in width, see the `width` key of entries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridLayout {
+    /// Width available to the rows
+    pub available: f32,
+    /// Gap between the entries of a row
+    pub gap: f32,
+    /// Fixed number of entries per row, at least one
+    pub columns: Option<usize>,
+}
+
+impl GridLayout {
+    /// Rows fitting into `available` width, `columns` overrides the number of entries per row
+    pub fn new(available: f32, gap: f32, columns: Option<u64>) -> Self {
+        Self {
+            available,
+            gap,
+            columns: columns.map(|columns| (columns as usize).max(1)),
+        }
+    }
+
+    /// Whether an entry `width` wide fits behind the entries `row` wide
+    /// the first entry of a row always fits
+    pub fn fits(&self, row: &[f32], width: f32) -> bool {
+        self.columns.map_or_else(
+            || row.is_empty() || row_width(row, self.gap) + self.gap + width <= self.available,
+            |columns| row.len() < columns,
+        )
+    }
+
+    /// Space left of `rows` to center the widest of them in the available width
+    pub fn offset<'a>(&self, rows: impl IntoIterator<Item = &'a [f32]>) -> f32 {
+        let widest = rows
+            .into_iter()
+            .map(|row| row_width(row, self.gap))
+            .fold(0.0, f32::max);
+        ((self.available - widest) / 2.0).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use super::{GridLayout, columns, menu_width, row_width};
+
+    #[test]
+    fn uniform_columns() {
+        // 3 * 100 + 2 * 10 fits exactly, a fourth column does not
+        assert_eq!(columns(320.0, 100.0, 10.0), 3);
+        assert_eq!(columns(319.0, 100.0, 10.0), 2);
+        assert_eq!(columns(50.0, 100.0, 10.0), 1);
+        assert!((row_width(&[100.0; 3], 10.0) - 320.0).abs() < f32::EPSILON);
+        assert!(row_width(&[], 10.0).abs() < f32::EPSILON);
+        assert!((menu_width(1000.0, 10.0) - 960.0).abs() < f32::EPSILON)
+    }
+
+    #[test]
+    fn fits() {
+        let layout = GridLayout::new(320.0, 10.0, None);
+        assert!(layout.fits(&[], 500.0));
+        assert!(layout.fits(&[100.0, 100.0], 100.0));
+        assert!(!layout.fits(&[100.0, 100.0], 101.0));
+        assert!(layout.fits(&[200.0], 110.0));
+        let layout = GridLayout::new(0.0, 10.0, Some(0));
+        assert!(layout.fits(&[], 100.0));
+        assert!(!layout.fits(&[100.0], 100.0));
+        let layout = GridLayout::new(100.0, 10.0, Some(5));
+        assert!(layout.fits(&[100.0; 4], 100.0))
+    }
+
+    #[test]
+    fn centered() {
+        let layout = GridLayout::new(400.0, 10.0, None);
+        let rows: [&[f32]; 2] = [&[100.0; 3], &[100.0]];
+        assert!((layout.offset(rows) - 40.0).abs() < f32::EPSILON);
+        let layout = GridLayout::new(50.0, 10.0, None);
+        assert!(layout.offset([&[100.0][..]]).abs() < f32::EPSILON)
+    }
+}
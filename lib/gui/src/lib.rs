@@ -1,37 +1,38 @@
-use config::AppConfig;
+use config::{AppConfig, Override};
+use fonts::Fonts;
 use iced::{
-    Color, Element, Task, application, application::Appearance, event, event::Event, keyboard,
-    window, window::Settings,
+    Color, Element, Font, Task, application, application::Appearance, event, event::Event,
+    keyboard, window, window::Settings,
 };
 use std::process::exit;
 
 mod errors;
 mod events;
+mod fonts;
+mod layout;
 mod menu_widget;
+pub mod paths;
 mod state;
 pub use errors::Error;
 use events::update;
 use menu_widget::EntryWidget;
+use paths::config_paths;
 use state::{MenuState, Message};
 
-pub const ENTRY_PATHS: [&str; 2] = ["~/.config/tvmenu/entries", "./entries"];
-pub const EXIT_BUTTON: &str = "assets/exit.png";
-pub const LOCK_BUTTON: &str = "assets/lock.png";
-pub const REBOOT_BUTTON: &str = "assets/reboot.png";
-pub const SHUTDOWN_BUTTON: &str = "assets/shutdown.png";
-pub const CONFIG_NAMES: [&str; 4] = [
-    "~/.config/tvmenu.conf",
-    "~/.config/tvmenu/config",
-    "~/.config/tvmenu/tvmenu.conf",
-    "./tvmenu.conf",
-];
+/// Prefix of environment variables overriding config values
+pub const ENV_PREFIX: &str = "TVMENU_";
 
 /// Run the app
+/// config values are overridden by environment variables (see [`ENV_PREFIX`])
+/// followed by the given `overrides`
 /// # Errors
 /// Returns an error if the config could not be loaded
-/// or when the [`iced::Application`] returns an error
-pub fn run_app() -> Result<(), Error> {
-    let mut config = AppConfig::load(&CONFIG_NAMES)?;
+/// or when the [`iced::Application`] returns an error,
+/// fonts that cannot be loaded are reported and replaced by the default font
+pub fn run_app(overrides: Vec<Override>) -> Result<(), Error> {
+    let mut all_overrides = Override::from_env(ENV_PREFIX);
+    all_overrides.extend(overrides);
+    let mut config = AppConfig::load(&config_paths(), &all_overrides)?;
     let mut window_settings = Settings::default();
     if config.height == 0.0 {
         config.height = window_settings.size.height;
@@ -45,8 +46,10 @@ pub fn run_app() -> Result<(), Error> {
     }
 
     let (w, h) = (window_settings.size.width, window_settings.size.height);
+    let fonts = Fonts::load(&config);
+    let entry_font = fonts.entries;
 
-    let app = application("TV Menu", update, view)
+    let mut app = application("TV Menu", update, view)
         .style(|state, _| Appearance {
             background_color: to_color(&state.config.background),
             text_color: to_color(&state.config.text_color),
@@ -58,23 +61,33 @@ pub fn run_app() -> Result<(), Error> {
                     width: size.width,
                     height: size.height,
                 }),
-                Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => {
-                    Some(Message::KeyPress(key))
+                Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                    Some(Message::KeyPress(key, modifiers))
                 }
                 _ => None,
             })
-        });
+        })
+        .default_font(fonts.window);
+    for data in fonts.data {
+        app = app.font(data);
+    }
 
-    app.run_with(move || setup_app(config, w, h))?;
+    app.run_with(move || setup_app(config, entry_font, w, h))?;
     Ok(())
 }
 
 fn setup_app(
     config: AppConfig,
+    entry_font: Font,
     window_width: f32,
     window_height: f32,
 ) -> (MenuState, Task<Message>) {
-    let state = report_err(MenuState::from_config(config, window_width, window_height));
+    let state = report_err(MenuState::from_config(
+        config,
+        entry_font,
+        window_width,
+        window_height,
+    ));
     (state, Task::none())
 }
 
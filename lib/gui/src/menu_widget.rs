@@ -1,73 +1,275 @@
 use crate::{Message, to_color};
-use config::AppConfig;
+use config::{AppConfig, Metrics, TileLayout, TitleAlign, TitleOverflow};
 use entries::MenuEntry;
 use iced::{
-    Border, Color, Element, Length,
-    widget::{Button, Column, Container, button, container, image, text},
+    Border, Color, ContentFit, Element, Font, Length,
+    alignment::{Horizontal, Vertical},
+    widget::{
+        Button, Column, Container, Row, Stack, button, container, image, text, text::Wrapping,
+    },
 };
 use std::path::PathBuf;
 
+/// Average width of a character relative to the text size, used to shorten titles
+const CHAR_WIDTH: f32 = 0.55;
+
+/// Text size of `entry` in pixels, set by its style or the `[Entries]` section
+fn entry_text_size(entry: &MenuEntry, metrics: &Metrics) -> f32 {
+    entry
+        .style
+        .text_size
+        .map_or(metrics.entries.text_size, |size| {
+            metrics.vertical(size, metrics.text_size)
+        })
+}
+
+/// Width of `entry` in pixels, set by its style or the `[Entries]` section
+pub fn entry_width(entry: &MenuEntry, metrics: &Metrics) -> f32 {
+    entry.style.width.map_or(metrics.entries.width, |size| {
+        metrics.horizontal(size, entry_text_size(entry, metrics))
+    })
+}
+
 pub struct EntryWidget {
     active: bool,
+    index: usize,
     title: String,
-    launch: String,
-    args: Vec<String>,
     icon: Option<PathBuf>,
+    description: Option<String>,
+    poster: Option<PathBuf>,
     text_size: f32,
+    font: Font,
     height: f32,
     width: f32,
     text_color: Color,
     background: Color,
     background_active: Color,
     border_radius: f32,
+    tile: TileLayout,
+    title_align: TitleAlign,
+    title_overflow: TitleOverflow,
 }
 
 impl EntryWidget {
-    pub fn new(entry: &MenuEntry, conf: &AppConfig, active: bool) -> Self {
+    /// Widget for `entry`, which has the index `index` in [`crate::MenuState::entries`]
+    /// the style of the entry takes precedence over `conf.entries`, sizes are resolved by `metrics`
+    pub fn new(
+        index: usize,
+        entry: &MenuEntry,
+        conf: &AppConfig,
+        metrics: &Metrics,
+        font: Font,
+        active: bool,
+    ) -> Self {
+        let style = &entry.style;
+        let text_size = entry_text_size(entry, metrics);
         Self {
             active,
+            index,
             title: entry.title.clone(),
-            launch: entry.launch.clone(),
-            args: entry.args.clone(),
             icon: entry.icon.clone(),
-            text_size: conf.entries.text_size,
-            height: conf.entries.height,
-            width: conf.entries.width,
-            text_color: to_color(&conf.entries.text_color),
-            background: to_color(&conf.entries.background),
-            background_active: to_color(&conf.entries.background_active),
-            border_radius: conf.entries.border_radius,
+            description: entry.description.clone(),
+            poster: style.poster.clone(),
+            text_size,
+            font,
+            height: style.height.map_or(metrics.entries.height, |size| {
+                metrics.vertical(size, text_size)
+            }),
+            width: entry_width(entry, metrics),
+            text_color: to_color(&style.text_color.unwrap_or(conf.entries.text_color)),
+            background: to_color(&style.background.unwrap_or(conf.entries.background)),
+            background_active: to_color(
+                &style
+                    .background_active
+                    .unwrap_or(conf.entries.background_active),
+            ),
+            border_radius: style
+                .border_radius
+                .map_or(metrics.entries.border_radius, |size| {
+                    metrics.vertical(size, text_size)
+                }),
+            tile: conf.entries.tile,
+            title_align: conf.entries.title_align,
+            title_overflow: conf.entries.title_overflow,
         }
     }
 
-    pub fn view<'a>(self) -> Button<'a, Message> {
-        let title = text(self.title)
-            .height(Length::Fixed(self.text_size))
+    /// Enlarge the entry by `factor`, used for the selected entry of the carousel
+    #[must_use]
+    pub fn scaled(mut self, factor: f32) -> Self {
+        self.text_size *= factor;
+        self.width *= factor;
+        self.height *= factor;
+        self.border_radius *= factor;
+        self
+    }
+
+    /// The title, shortened or wrapped as set by `title-overflow` to fit into `width`
+    fn view_title<'a>(&self, width: f32) -> Element<'a, Message> {
+        let title = match self.title_overflow {
+            TitleOverflow::Ellipsis => ellipsize(&self.title, width, self.text_size),
+            TitleOverflow::Clip | TitleOverflow::Wrap => self.title.clone(),
+        };
+        let (height, wrapping) = match self.title_overflow {
+            TitleOverflow::Wrap => (Length::Shrink, Wrapping::Word),
+            TitleOverflow::Clip | TitleOverflow::Ellipsis => {
+                (Length::Fixed(self.text_size * 1.3), Wrapping::None)
+            }
+        };
+        let align = match self.title_align {
+            TitleAlign::Left => Horizontal::Left,
+            TitleAlign::Center => Horizontal::Center,
+            TitleAlign::Right => Horizontal::Right,
+        };
+        text(title)
+            .size(self.text_size)
+            .font(self.font)
+            .height(height)
             .width(Length::Fill)
-            .center();
-        let image: Element<Message> = self.icon.map_or_else(
+            .wrapping(wrapping)
+            .align_x(align)
+            .align_y(Vertical::Center)
+            .into()
+    }
+
+    /// The icon filling the available space, or nothing if the entry has no icon
+    fn view_icon<'a>(icon: Option<PathBuf>, content_fit: ContentFit) -> Element<'a, Message> {
+        icon.map_or_else(
             || text("").height(Length::Fill).width(Length::Fill).into(),
-            |icon| image(icon).height(Length::Fill).width(Length::Fill).into(),
-        );
+            |icon| {
+                image(icon)
+                    .content_fit(content_fit)
+                    .height(Length::Fill)
+                    .width(Length::Fill)
+                    .into()
+            },
+        )
+    }
 
-        let column = Column::new()
-            .height(self.height)
-            .width(self.width)
-            .push(image)
-            .push(title);
+    /// Icon and title arranged as set by `tile`
+    fn view_content<'a>(&mut self) -> Element<'a, Message> {
+        let title = self.view_title(self.width);
+        match self.tile {
+            TileLayout::IconAbove => Column::new()
+                .push(Self::view_icon(self.icon.take(), ContentFit::Contain))
+                .push(title)
+                .into(),
+            TileLayout::IconOnly => Self::view_icon(self.icon.take(), ContentFit::Contain),
+            TileLayout::TitleOnly => Container::new(title).center_y(Length::Fill).into(),
+            TileLayout::IconLeft => {
+                let title = self.view_title(self.width - self.height);
+                Row::new()
+                    .push(
+                        Container::new(Self::view_icon(self.icon.take(), ContentFit::Contain))
+                            .width(self.height)
+                            .height(Length::Fill),
+                    )
+                    .push(Container::new(title).center_y(Length::Fill))
+                    .spacing(self.text_size / 2.0)
+                    .into()
+            }
+            TileLayout::Poster => {
+                let image = self.poster.take().or_else(|| self.icon.take());
+                let band = Container::new(title)
+                    .padding([self.text_size / 4.0, self.text_size / 2.0])
+                    .width(Length::Fill)
+                    .style(|_| {
+                        container::Style::default().background(Color::from_rgba(0.0, 0.0, 0.0, 0.6))
+                    });
+                Stack::new()
+                    .push(Self::view_icon(image, ContentFit::Cover))
+                    .push(Container::new(band).align_bottom(Length::Fill))
+                    .into()
+            }
+        }
+    }
+
+    /// The entry as a row of a list `width` wide, with the icon left of title and description
+    pub fn view_list<'a>(mut self, width: f32) -> Button<'a, Message> {
+        let text_width = width - self.height - self.text_size;
+        let mut text_column = Column::new().push(self.view_title(text_width));
+        if let Some(description) = self.description.take() {
+            text_column = text_column.push(
+                text(description)
+                    .size(self.text_size * 0.8)
+                    .font(self.font)
+                    .width(Length::Fill)
+                    .wrapping(Wrapping::Word),
+            );
+        }
+        let content = Row::new()
+            .push(
+                Container::new(Self::view_icon(self.icon.take(), ContentFit::Contain))
+                    .width(self.height)
+                    .height(Length::Fill),
+            )
+            .push(Container::new(text_column).center_y(Length::Fill))
+            .spacing(self.text_size);
+        self.width = width;
+        self.view_container(content.into())
+    }
+
+    pub fn view<'a>(mut self) -> Button<'a, Message> {
+        let content = self.view_content();
+        self.view_container(content)
+    }
+
+    /// `content` on the background of the entry, pressing it launches the entry
+    fn view_container(self, content: Element<'_, Message>) -> Button<'_, Message> {
         let background = if self.active {
             self.background_active
         } else {
             self.background
         };
-        let container = Container::new(column).style(move |_| {
-            container::Style::default()
-                .color(self.text_color)
-                .background(background)
-                .border(Border::default().rounded(self.border_radius))
-        });
+        let content: Element<Message> = match self.poster {
+            Some(poster) => Stack::new()
+                .push(
+                    image(poster)
+                        .content_fit(ContentFit::Cover)
+                        .height(Length::Fill)
+                        .width(Length::Fill),
+                )
+                .push(content)
+                .into(),
+            None => content,
+        };
+        let container = Container::new(content)
+            .height(self.height)
+            .width(self.width)
+            .style(move |_| {
+                container::Style::default()
+                    .color(self.text_color)
+                    .background(background)
+                    .border(Border::default().rounded(self.border_radius))
+            });
         Button::new(container)
-            .on_press(Message::Launch(self.launch.clone(), self.args.clone()))
+            .on_press(Message::Launch(self.index))
             .style(|_, _| button::Style::default())
     }
 }
+
+/// Shorten `title` to fit into `width` at `text_size`, ending with `…` if it was shortened
+/// the width of characters is estimated, see [`CHAR_WIDTH`]
+fn ellipsize(title: &str, width: f32, text_size: f32) -> String {
+    let max_chars = (width / (text_size * CHAR_WIDTH)).floor().max(1.0) as usize;
+    if title.chars().count() <= max_chars {
+        return title.to_owned();
+    }
+    let mut short: String = title.chars().take(max_chars - 1).collect();
+    short.truncate(short.trim_end().len());
+    short.push('…');
+    short
+}
+
+#[cfg(test)]
+mod menu_widget_tests {
+    use super::ellipsize;
+
+    #[test]
+    fn ellipsis() {
+        assert_eq!(ellipsize("Kodi", 100.0, 10.0), "Kodi");
+        assert_eq!(ellipsize("Steam Big Picture", 55.0, 10.0), "Steam Big…");
+        assert_eq!(ellipsize("Steam Big Picture", 40.0, 10.0), "Steam…");
+        assert_eq!(ellipsize("Kodi", 1.0, 10.0), "…")
+    }
+}
@@ -0,0 +1,156 @@
+use config::xdg::{APP_DIR, config_search_dirs, data_search_dirs, find_file, state_home};
+use iced::widget::image::Handle;
+use std::path::PathBuf;
+
+/// Config file names, relative to each config directory
+pub const CONFIG_NAMES: [&str; 3] = ["tvmenu.conf", "tvmenu/config", "tvmenu/tvmenu.conf"];
+/// Name of the entries directory inside the app directory
+pub const ENTRY_DIR: &str = "entries";
+/// Name of the assets directory inside the app directory
+pub const ASSET_DIR: &str = "assets";
+/// Name of the launch history file inside the app state directory
+pub const HISTORY_FILE: &str = "history";
+/// Name of the favorites file inside the app state directory
+pub const FAVORITES_FILE: &str = "favorites";
+
+/// An image used by the ui, with an embedded default
+pub struct Asset {
+    name: &'static str,
+    fallback: &'static [u8],
+}
+
+pub const EXIT_BUTTON: Asset = Asset {
+    name: "exit.png",
+    fallback: include_bytes!("../../../assets/exit.png"),
+};
+pub const LOCK_BUTTON: Asset = Asset {
+    name: "lock.png",
+    fallback: include_bytes!("../../../assets/lock.png"),
+};
+pub const REBOOT_BUTTON: Asset = Asset {
+    name: "reboot.png",
+    fallback: include_bytes!("../../../assets/reboot.png"),
+};
+pub const SHUTDOWN_BUTTON: Asset = Asset {
+    name: "shutdown.png",
+    fallback: include_bytes!("../../../assets/shutdown.png"),
+};
+
+impl Asset {
+    /// Load the asset from the first data directory containing it
+    /// falls back to the embedded image if it is not found
+    #[must_use]
+    pub fn load(&self) -> Handle {
+        find_file(&data_search_dirs(), &format!("{ASSET_DIR}/{}", self.name))
+            .map_or_else(|| Handle::from_bytes(self.fallback), Handle::from_path)
+    }
+}
+
+/// Paths to search for the config file, in order
+/// `$XDG_CONFIG_HOME`, then `$XDG_CONFIG_DIRS` and finally the working directory
+#[must_use]
+pub fn config_paths() -> Vec<PathBuf> {
+    config_paths_in(config_search_dirs())
+}
+
+/// Config files inside `config_dirs` in order, followed by the working directory
+fn config_paths_in(config_dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = config_dirs
+        .into_iter()
+        .flat_map(|dir| CONFIG_NAMES.map(|name| dir.join(name)))
+        .collect();
+    paths.push(PathBuf::from("./tvmenu.conf"));
+    paths
+}
+
+/// Directories to search for menu entries, in order
+/// `$XDG_CONFIG_HOME`, `$XDG_CONFIG_DIRS`, `$XDG_DATA_HOME`, `$XDG_DATA_DIRS`
+/// and finally the working directory
+#[must_use]
+pub fn entry_paths() -> Vec<PathBuf> {
+    entry_paths_in(config_search_dirs(), data_search_dirs())
+}
+
+/// Entry directories inside `config_dirs` and then `data_dirs`, followed by the working directory
+fn entry_paths_in(config_dirs: Vec<PathBuf>, data_dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = config_dirs
+        .into_iter()
+        .chain(data_dirs)
+        .map(|dir| dir.join(APP_DIR).join(ENTRY_DIR))
+        .collect();
+    paths.push(PathBuf::from("./entries"));
+    paths
+}
+
+/// Path of the launch history, `$XDG_STATE_HOME/tvmenu/history`
+/// `None` if the state directory could not be determined
+#[must_use]
+pub fn history_path() -> Option<PathBuf> {
+    state_home().map(|dir| dir.join(APP_DIR).join(HISTORY_FILE))
+}
+
+/// Path of the entries pinned at runtime, `$XDG_STATE_HOME/tvmenu/favorites`
+/// `None` if the state directory could not be determined
+#[must_use]
+pub fn favorites_path() -> Option<PathBuf> {
+    state_home().map(|dir| dir.join(APP_DIR).join(FAVORITES_FILE))
+}
+
+/// Icons for the power buttons
+pub struct PowerIcons {
+    pub exit: Handle,
+    pub lock: Handle,
+    pub reboot: Handle,
+    pub shutdown: Handle,
+}
+
+impl PowerIcons {
+    /// Load all power icons, see [`Asset::load`]
+    #[must_use]
+    pub fn load() -> Self {
+        Self {
+            exit: EXIT_BUTTON.load(),
+            lock: LOCK_BUTTON.load(),
+            reboot: REBOOT_BUTTON.load(),
+            shutdown: SHUTDOWN_BUTTON.load(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod paths_tests {
+    use super::{config_paths_in, entry_paths_in};
+    use std::path::PathBuf;
+
+    #[test]
+    fn config_order() {
+        let paths = config_paths_in(vec!["/home/tv/.config".into(), "/etc/xdg".into()]);
+        let expected: Vec<PathBuf> = [
+            "/home/tv/.config/tvmenu.conf",
+            "/home/tv/.config/tvmenu/config",
+            "/home/tv/.config/tvmenu/tvmenu.conf",
+            "/etc/xdg/tvmenu.conf",
+            "/etc/xdg/tvmenu/config",
+            "/etc/xdg/tvmenu/tvmenu.conf",
+            "./tvmenu.conf",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+        assert_eq!(paths, expected)
+    }
+
+    #[test]
+    fn entry_order() {
+        let paths = entry_paths_in(vec!["/etc/xdg".into()], vec!["/usr/share".into()]);
+        let expected: Vec<PathBuf> = [
+            "/etc/xdg/tvmenu/entries",
+            "/usr/share/tvmenu/entries",
+            "./entries",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+        assert_eq!(paths, expected)
+    }
+}
@@ -1,31 +1,43 @@
 use crate::{
-    ENTRY_PATHS, EXIT_BUTTON, EntryWidget, Error, LOCK_BUTTON, REBOOT_BUTTON, SHUTDOWN_BUTTON,
+    EntryWidget, Error,
+    layout::{GridLayout, columns, menu_width},
+    menu_widget::entry_width,
+    paths::{PowerIcons, entry_paths, favorites_path, history_path},
     to_color,
 };
-use config::AppConfig;
-use entries::MenuEntry;
+use config::{AppConfig, CategoryLayout, MenuLayout, Metrics};
+use entries::{EntryKind, Favorites, History, MenuEntry, sort_entries, unix_time};
 use iced::{
-    Background, Border, Element, Length,
-    alignment::Horizontal,
-    keyboard::Key,
+    Background, Border, Element, Font, Length, Padding,
+    alignment::{Horizontal, Vertical},
+    keyboard::{Key, Modifiers},
     widget::{
         Button, Column, Container, Row, Scrollable, Text, TextInput, button, image,
         scrollable::{Direction, Scrollbar},
         text_input,
     },
 };
+use std::{
+    collections::HashMap,
+    mem::{replace, take},
+    ops::Range,
+};
 
 /// Messages sent to [`crate::update`]
 #[derive(Debug, Clone)]
 pub enum Message {
-    /// Launch an app with command and arguments
-    Launch(String, Vec<String>),
+    /// Launch the entry with the given index
+    Launch(usize),
     /// Window was resized
     Resized { width: f32, height: f32 },
-    /// A Key was pressed
-    KeyPress(Key),
+    /// A Key was pressed with the given modifiers
+    KeyPress(Key, Modifiers),
     /// Contents of the filter input changed
     FilterChanged(String),
+    /// Show the category with the given index
+    SelectCategory(usize),
+    /// Close the open folder
+    Back,
     /// Exit the app
     Exit,
     /// Lock the screen
@@ -36,54 +48,388 @@ pub enum Message {
     Shutdown,
 }
 
+/// Title of the category of entries without category
+pub const OTHER_CATEGORY: &str = "Other";
+
+/// Factor by which the selected entry of the carousel is enlarged
+const CAROUSEL_ZOOM: f32 = 1.25;
+
+/// A row of entries as shown in the menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuRow {
+    /// Title shown above the row
+    pub title: Option<String>,
+    /// Indices of the entries in the row
+    pub entries: Vec<usize>,
+}
+
+/// A menu level left by opening a folder, restored when going back
+pub struct Level {
+    /// entries of the level, the opened folder is left without entries
+    pub entries: Vec<MenuEntry>,
+    /// index of the opened folder in `entries`
+    pub folder: usize,
+    /// selected item of the level
+    pub selected_index: usize,
+    /// selected top row of the level
+    pub selected_top: Option<usize>,
+    /// categories of the level
+    pub categories: Vec<Option<String>>,
+    /// selected category of the level
+    pub selected_category: usize,
+}
+
 /// State of the App
 pub struct MenuState {
     /// configuration
     pub config: AppConfig,
     /// current window size
     pub window_size: (f32, f32),
-    /// menu entries
+    /// sizes of the config in pixels for the current window size
+    pub metrics: Metrics,
+    /// font of the entries, the window uses the default font of the app
+    pub entry_font: Font,
+    /// menu entries of the current level
     pub entries: Vec<MenuEntry>,
+    /// levels left by opening folders, the last one contains the open folder
+    pub levels: Vec<Level>,
+    /// selected item of folders that were closed, by the indices of the folders leading to them
+    pub folder_selections: HashMap<Vec<usize>, usize>,
     /// currently selected item
     pub selected_index: usize,
+    /// index of the top row containing the selected item, `None` if it is in the menu
+    pub selected_top: Option<usize>,
+    /// rows shown above the menu while the filter is empty, e.g. the favorites
+    pub top_rows: Vec<MenuRow>,
+    /// categories of the entries in order, `None` for entries without category
+    pub categories: Vec<Option<String>>,
+    /// index of the category shown with [`CategoryLayout::Tabs`]
+    pub selected_category: usize,
+    /// launch history
+    pub history: History,
+    /// entries pinned or unpinned at runtime
+    pub favorites: Favorites,
     /// invisible entries (used for filtering)
     pub disabled_indices: Vec<usize>,
     /// current value of the filter input
     pub filter_value: String,
+    /// icons of the power buttons
+    pub power_icons: PowerIcons,
 }
 
 impl MenuState {
+    /// Load the menu entries, sorted as set by [`AppConfig::sort`]
+    /// # Errors
+    /// Returns an error if no menu entries could be loaded
     pub fn from_config(
         config: AppConfig,
+        entry_font: Font,
         window_width: f32,
         window_height: f32,
     ) -> Result<Self, Error> {
-        let entries = MenuEntry::load_dirs(&ENTRY_PATHS)?;
-        Ok(Self {
+        let entries = MenuEntry::load_dirs(&entry_paths())?;
+        let history = load_history();
+        let order = (config.category_layout != CategoryLayout::Flat)
+            .then_some(config.categories.as_slice());
+        let entries = sort_entries(entries, config.sort, &history, order);
+        let categories = categories(&entries);
+        let mut state = Self {
             window_size: (window_width, window_height),
+            metrics: config.metrics(window_width, window_height),
+            entry_font,
             filter_value: String::new(),
             config,
             selected_index: 0,
+            selected_top: None,
+            top_rows: vec![],
+            categories,
+            selected_category: 0,
+            history,
+            favorites: load_favorites(),
             disabled_indices: Vec::with_capacity(entries.len()),
             entries,
-        })
+            levels: vec![],
+            folder_selections: HashMap::new(),
+            power_icons: PowerIcons::load(),
+        };
+        state.update_top_rows();
+        Ok(state)
     }
 
-    pub fn widgets_per_col(&self) -> u64 {
-        self.config.columns.map_or_else(
-            || {
-                ((self.window_size.0 - self.config.padding)
-                    / (self.config.entries.width + self.config.column_gap))
-                    .floor() as u64
-            },
-            |cols| cols,
+    /// Update the window size and the sizes depending on it
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.window_size = (width, height);
+        self.metrics = self.config.metrics(width, height);
+    }
+
+    /// Rebuild the favorites row and the row of recently launched entries
+    /// the selection is moved to the menu if its entry is no longer in its top row
+    pub fn update_top_rows(&mut self) {
+        let favorites: Vec<usize> = (0..self.entries.len())
+            .filter(|&ind| self.favorites.is_favorite(&self.entries[ind]))
+            .collect();
+        let recent: Vec<usize> = self
+            .history
+            .launched(&self.entries)
+            .take(self.config.recent)
+            .collect();
+        self.top_rows = [("Favorites", favorites), ("Recent", recent)]
+            .into_iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .map(|(title, entries)| MenuRow {
+                title: Some(title.to_owned()),
+                entries,
+            })
+            .collect();
+        self.selected_top = self.selected_top.filter(|&row| {
+            self.top_rows
+                .get(row)
+                .is_some_and(|top_row| top_row.entries.contains(&self.selected_index))
+        });
+    }
+
+    /// Indices of the open folders, from the top level to the current one
+    pub fn folder_path(&self) -> Vec<usize> {
+        self.levels.iter().map(|level| level.folder).collect()
+    }
+
+    /// Open the folder with index `ind`, restoring its selection if it was opened before
+    pub fn open_folder(&mut self, ind: usize) {
+        let Some(EntryKind::Folder { entries, .. }) =
+            self.entries.get_mut(ind).map(|entry| &mut entry.kind)
+        else {
+            return;
+        };
+        let folder_entries = take(entries);
+        self.levels.push(Level {
+            categories: replace(&mut self.categories, categories(&folder_entries)),
+            entries: replace(&mut self.entries, folder_entries),
+            folder: ind,
+            selected_index: self.selected_index,
+            selected_top: self.selected_top.take(),
+            selected_category: self.selected_category,
+        });
+        self.selected_index = self
+            .folder_selections
+            .get(&self.folder_path())
+            .copied()
+            .unwrap_or_default();
+        self.selected_category = 0;
+        self.reset_level();
+    }
+
+    /// Close the open folder and restore the selection of the level containing it
+    /// returns `false` if no folder is open
+    pub fn close_folder(&mut self) -> bool {
+        let path = self.folder_path();
+        let Some(level) = self.levels.pop() else {
+            return false;
+        };
+        self.folder_selections.insert(path, self.selected_index);
+        let folder_entries = replace(&mut self.entries, level.entries);
+        if let Some(EntryKind::Folder { entries, .. }) = self
+            .entries
+            .get_mut(level.folder)
+            .map(|entry| &mut entry.kind)
+        {
+            *entries = folder_entries;
+        }
+        self.categories = level.categories;
+        self.selected_index = level.selected_index;
+        self.selected_category = level.selected_category;
+        self.reset_level();
+        self.selected_top = level.selected_top;
+        true
+    }
+
+    /// Clear the filter and rebuild the top rows after changing the level
+    fn reset_level(&mut self) {
+        self.filter_value.clear();
+        self.disabled_indices.clear();
+        self.selected_top = None;
+        self.update_top_rows();
+    }
+
+    /// Titles of the open folders, from the top level to the current one
+    pub fn breadcrumb(&self) -> Vec<&str> {
+        self.levels
+            .iter()
+            .map(|level| level.entries[level.folder].title.as_str())
+            .collect()
+    }
+
+    /// Title of the category with index `ind`
+    pub fn category_title(&self, ind: usize) -> &str {
+        self.categories
+            .get(ind)
+            .and_then(Option::as_deref)
+            .unwrap_or(OTHER_CATEGORY)
+    }
+
+    /// Show the category after the selected one, wrapping around after the last
+    pub fn next_category(&mut self) {
+        let len = self.categories.len().max(1);
+        self.selected_category = (self.selected_category + 1) % len;
+    }
+
+    /// Show the category before the selected one, wrapping around before the first
+    pub fn previous_category(&mut self) {
+        let len = self.categories.len().max(1);
+        self.selected_category = (self.selected_category + len - 1) % len;
+    }
+
+    /// Whether the tab bar is shown, that is for [`CategoryLayout::Tabs`] while not filtering
+    pub fn shows_tabs(&self) -> bool {
+        self.config.category_layout == CategoryLayout::Tabs && self.filter_value.is_empty()
+    }
+
+    /// Indices of the entries shown in the menu, that is entries matching the filter
+    /// and, when showing tabs, belonging to the selected category
+    pub fn menu_entries(&self) -> Vec<usize> {
+        let category = self
+            .shows_tabs()
+            .then(|| self.categories.get(self.selected_category))
+            .flatten();
+        (0..self.entries.len())
+            .filter(|ind| !self.disabled_indices.contains(ind))
+            .filter(|&ind| category.is_none_or(|cat| *cat == self.entries[ind].category))
+            .collect()
+    }
+
+    /// All rows as shown, the top rows followed by the rows of the menu
+    /// with [`CategoryLayout::Headers`] each category starts a new row titled by the category,
+    /// except for [`MenuLayout::Carousel`] which shows the menu in a single row.
+    /// Rows of [`MenuLayout::Grid`] take as many entries as fit, see [`GridLayout::fits`]
+    pub fn rows(&self) -> Vec<MenuRow> {
+        let grid = self.grid_layout();
+        let headers = self.config.category_layout == CategoryLayout::Headers
+            && self.config.layout != MenuLayout::Carousel;
+        let mut rows = self.visible_top_rows().to_vec();
+        let mut current: Option<&Option<String>> = None;
+        // widths of the entries in the last row
+        let mut row_widths = vec![];
+        for ind in self.menu_entries() {
+            let category = &self.entries[ind].category;
+            let new_category = headers && current != Some(category);
+            let width = self.entry_width(ind);
+            let fits = match self.config.layout {
+                MenuLayout::Grid => grid.fits(&row_widths, width),
+                MenuLayout::List => false,
+                MenuLayout::Carousel => true,
+            };
+            match rows.last_mut() {
+                Some(row) if !new_category && current.is_some() && fits => {
+                    row.entries.push(ind);
+                }
+                _ => {
+                    row_widths.clear();
+                    rows.push(MenuRow {
+                        title: new_category
+                            .then(|| category.as_deref().unwrap_or(OTHER_CATEGORY).to_owned()),
+                        entries: vec![ind],
+                    });
+                }
+            }
+            row_widths.push(width);
+            current = Some(category);
+        }
+        rows
+    }
+
+    /// Width of the entry with index `ind` in pixels
+    fn entry_width(&self, ind: usize) -> f32 {
+        entry_width(&self.entries[ind], &self.metrics)
+    }
+
+    /// Row and column of the selected entry in `rows`, see [`Self::rows`]
+    /// falls back to the first entry of the menu if the selected entry is not shown
+    pub fn selected_position(&self, rows: &[MenuRow]) -> Option<(usize, usize)> {
+        let num_top = self.visible_top_rows().len();
+        let find = |row: usize| {
+            rows[row]
+                .entries
+                .iter()
+                .position(|&ind| ind == self.selected_index)
+                .map(|col| (row, col))
+        };
+        if let Some(row) = self.selected_top.filter(|&row| row < num_top)
+            && let Some(position) = find(row)
+        {
+            return Some(position);
+        }
+        (num_top..rows.len())
+            .find_map(find)
+            .or_else(|| (num_top < rows.len()).then_some((num_top, 0)))
+            .or_else(|| (!rows.is_empty()).then_some((0, 0)))
+    }
+
+    /// Select the entry at `row` and `col` of `rows`, see [`Self::rows`]
+    pub fn select(&mut self, rows: &[MenuRow], row: usize, col: usize) {
+        let Some(&ind) = rows.get(row).and_then(|menu_row| menu_row.entries.get(col)) else {
+            return;
+        };
+        self.selected_index = ind;
+        self.selected_top = (row < self.visible_top_rows().len()).then_some(row);
+    }
+
+    /// Index of the selected entry, `None` if no entry is shown
+    pub fn selected_entry(&self) -> Option<usize> {
+        let rows = self.rows();
+        self.selected_position(&rows)
+            .map(|(row, col)| rows[row].entries[col])
+    }
+
+    /// Pin or unpin the entry with index `ind` and save the favorites
+    /// failing to save the favorites is reported but does not stop the menu
+    pub fn toggle_favorite(&mut self, ind: usize) {
+        let Some(entry) = self.entries.get(ind) else {
+            return;
+        };
+        self.favorites.toggle(entry);
+        if let Some(path) = favorites_path()
+            && let Err(err) = self.favorites.save(&path)
+        {
+            eprintln!("Could not save favorites:\n{err}");
+        }
+        self.update_top_rows();
+    }
+
+    /// Rows shown above the menu, these are hidden while filtering
+    pub fn visible_top_rows(&self) -> &[MenuRow] {
+        if self.filter_value.is_empty() {
+            &self.top_rows
+        } else {
+            &[]
+        }
+    }
+
+    /// Record a launch of the entry with index `ind` and save the history
+    /// failing to save the history is reported but does not stop the menu
+    pub fn record_launch(&mut self, ind: usize) {
+        let Some(entry) = self.entries.get(ind) else {
+            return;
+        };
+        self.history.record(entry, unix_time());
+        if let Some(path) = history_path()
+            && let Err(err) = self.history.save(&path)
+        {
+            eprintln!("Could not save launch history:\n{err}");
+        }
+    }
+
+    /// Rows of the menu grid for the current window size, see [`AppConfig::columns`]
+    pub fn grid_layout(&self) -> GridLayout {
+        GridLayout::new(
+            menu_width(self.window_size.0, self.metrics.padding),
+            self.metrics.column_gap,
+            self.config.columns,
         )
     }
 
     pub fn view_filter(&self) -> Container<'_, Message> {
-        let filter_label = Text::new("Filter").size(self.config.text_size);
+        let filter_label = Text::new("Filter").size(self.metrics.text_size);
         let filter_input = TextInput::new(&self.filter_value, &self.filter_value)
-            .size(self.config.text_size)
+            .size(self.metrics.text_size)
             .width(Length::Fixed(self.window_size.0 * 0.45))
             .style(|_, _| text_input::Style {
                 background: Background::Color(to_color(&self.config.background)),
@@ -102,74 +448,159 @@ impl MenuState {
             .center_x(Length::Fill)
     }
 
+    /// Tab bar with a tab for each category, see [`CategoryLayout::Tabs`]
+    pub fn view_tabs(&self) -> Container<'_, Message> {
+        let tabs = (0..self.categories.len()).map(|ind| {
+            let active = ind == self.selected_category;
+            Button::new(Text::new(self.category_title(ind)).size(self.metrics.text_size))
+                .style(move |_, _| button::Style {
+                    background: active.then(|| {
+                        Background::Color(to_color(&self.config.entries.background_active))
+                    }),
+                    text_color: if active {
+                        to_color(&self.config.entries.text_color)
+                    } else {
+                        to_color(&self.config.text_color)
+                    },
+                    border: Border::default().rounded(self.metrics.entries.border_radius),
+                    ..Default::default()
+                })
+                .on_press(Message::SelectCategory(ind))
+                .into()
+        });
+        Container::new(tabs.collect::<Row<_>>().spacing(self.metrics.column_gap))
+            .center_x(Length::Fill)
+    }
+
     pub fn view_menu(&self) -> Scrollable<'_, Message> {
-        let widgets_per_col = self.widgets_per_col();
+        let menu_rows = self.rows();
+        let selected = self.selected_position(&menu_rows);
+        let offset = if self.config.layout == MenuLayout::Grid && self.config.center_grid {
+            let widths: Vec<Vec<f32>> = menu_rows
+                .iter()
+                .map(|row| {
+                    row.entries
+                        .iter()
+                        .map(|&ind| self.entry_width(ind))
+                        .collect()
+                })
+                .collect();
+            self.grid_layout().offset(widths.iter().map(Vec::as_slice))
+        } else {
+            0.0
+        };
         let mut rows: Vec<Element<Message>> = vec![];
-        let mut current_row = Row::new().spacing(self.config.column_gap);
-        let mut num_elements = 0;
-        for (ind, entry) in self.entries.iter().enumerate() {
-            if self.disabled_indices.contains(&ind) {
-                continue;
-            }
-            let button = EntryWidget::new(entry, &self.config, ind == self.selected_index).view();
-            current_row = current_row.push(button);
-            num_elements += 1;
-            if num_elements >= widgets_per_col {
-                rows.push(current_row.into());
-                num_elements = 0;
-                current_row = Row::new().spacing(self.config.column_gap);
+        for (row_ind, menu_row) in menu_rows.into_iter().enumerate() {
+            if let Some(title) = menu_row.title {
+                rows.push(Text::new(title).size(self.metrics.text_size).into());
             }
+            let selected_col = selected.and_then(|(row, col)| (row == row_ind).then_some(col));
+            rows.push(self.view_row(&menu_row.entries, selected_col));
         }
-        if num_elements != 0 {
-            rows.push(current_row.into());
-        }
+        let direction = match self.config.layout {
+            MenuLayout::Grid => Direction::Both {
+                vertical: Scrollbar::new(),
+                horizontal: Scrollbar::new(),
+            },
+            MenuLayout::List | MenuLayout::Carousel => Direction::Vertical(Scrollbar::new()),
+        };
         Scrollable::new(
             Column::from_vec(rows)
-                .padding(self.config.padding)
-                .spacing(self.config.row_gap),
+                .padding(Padding {
+                    left: self.metrics.padding + offset,
+                    ..Padding::new(self.metrics.padding)
+                })
+                .spacing(self.metrics.row_gap),
         )
-        .direction(Direction::Both {
-            vertical: Scrollbar::new(),
-            horizontal: Scrollbar::new(),
-        })
+        .direction(direction)
         .width(Length::Fill)
         .height(Length::Fill)
     }
 
+    /// A row of entries as set by [`AppConfig::layout`], `selected_col` is the selected entry
+    fn view_row(&self, entries: &[usize], selected_col: Option<usize>) -> Element<'_, Message> {
+        let widget = |col: usize| {
+            let ind = entries[col];
+            EntryWidget::new(
+                ind,
+                &self.entries[ind],
+                &self.config,
+                &self.metrics,
+                self.entry_font,
+                selected_col == Some(col),
+            )
+        };
+        match self.config.layout {
+            MenuLayout::Grid => (0..entries.len())
+                .map(|col| widget(col).view().into())
+                .collect::<Row<_>>()
+                .spacing(self.metrics.column_gap)
+                .into(),
+            MenuLayout::List => {
+                let width = menu_width(self.window_size.0, self.metrics.padding);
+                (0..entries.len())
+                    .map(|col| widget(col).view_list(width).into())
+                    .collect::<Column<_>>()
+                    .into()
+            }
+            MenuLayout::Carousel => {
+                let visible = columns(
+                    menu_width(self.window_size.0, self.metrics.padding),
+                    self.metrics.entries.width,
+                    self.metrics.column_gap,
+                )
+                .saturating_sub(1);
+                let window = carousel_window(entries.len(), selected_col.unwrap_or(0), visible);
+                window
+                    .map(|col| {
+                        if selected_col == Some(col) {
+                            widget(col).scaled(CAROUSEL_ZOOM).view().into()
+                        } else {
+                            widget(col).view().into()
+                        }
+                    })
+                    .collect::<Row<_>>()
+                    .spacing(self.metrics.column_gap)
+                    .align_y(Vertical::Center)
+                    .into()
+            }
+        }
+    }
+
     fn view_power(&self) -> Container<'_, Message> {
-        let exit_button = Button::new(image(EXIT_BUTTON))
-            .height(self.config.text_size * 2.0)
+        let exit_button = Button::new(image(self.power_icons.exit.clone()))
+            .height(self.metrics.text_size * 2.0)
             .style(|_, _| button::Style {
                 background: Some(Background::Color(to_color(&self.config.entries.background))),
                 text_color: to_color(&self.config.entries.text_color),
-                border: Border::default().rounded(self.config.entries.border_radius),
+                border: Border::default().rounded(self.metrics.entries.border_radius),
                 ..Default::default()
             })
             .on_press(Message::Exit);
-        let lock_button = Button::new(image(LOCK_BUTTON))
-            .height(self.config.text_size * 2.0)
+        let lock_button = Button::new(image(self.power_icons.lock.clone()))
+            .height(self.metrics.text_size * 2.0)
             .style(|_, _| button::Style {
                 background: Some(Background::Color(to_color(&self.config.entries.background))),
                 text_color: to_color(&self.config.entries.text_color),
-                border: Border::default().rounded(self.config.entries.border_radius),
+                border: Border::default().rounded(self.metrics.entries.border_radius),
                 ..Default::default()
             })
             .on_press(Message::Lock);
-        let reboot_button = Button::new(image(REBOOT_BUTTON))
-            .height(self.config.text_size * 2.0)
+        let reboot_button = Button::new(image(self.power_icons.reboot.clone()))
+            .height(self.metrics.text_size * 2.0)
             .style(|_, _| button::Style {
                 background: Some(Background::Color(to_color(&self.config.entries.background))),
                 text_color: to_color(&self.config.entries.text_color),
-                border: Border::default().rounded(self.config.entries.border_radius),
+                border: Border::default().rounded(self.metrics.entries.border_radius),
                 ..Default::default()
             })
             .on_press(Message::Reboot);
-        let shutdown_button = Button::new(image(SHUTDOWN_BUTTON))
-            .height(self.config.text_size * 2.0)
+        let shutdown_button = Button::new(image(self.power_icons.shutdown.clone()))
+            .height(self.metrics.text_size * 2.0)
             .style(|_, _| button::Style {
                 background: Some(Background::Color(to_color(&self.config.entries.background))),
                 text_color: to_color(&self.config.entries.text_color),
-                border: Border::default().rounded(self.config.entries.border_radius),
+                border: Border::default().rounded(self.metrics.entries.border_radius),
                 ..Default::default()
             })
             .on_press(Message::Shutdown);
@@ -182,15 +613,99 @@ impl MenuState {
         .center_x(Length::Fill)
     }
 
+    /// Back button and the titles of the open folders
+    pub fn view_breadcrumb(&self) -> Container<'_, Message> {
+        let back = Button::new(Text::new("Back").size(self.metrics.text_size))
+            .style(|_, _| button::Style {
+                background: Some(Background::Color(to_color(&self.config.entries.background))),
+                text_color: to_color(&self.config.entries.text_color),
+                border: Border::default().rounded(self.metrics.entries.border_radius),
+                ..Default::default()
+            })
+            .on_press(Message::Back);
+        let path = Text::new(self.breadcrumb().join(" › ")).size(self.metrics.text_size);
+        Container::new(
+            Row::from_vec(vec![back.into(), path.into()])
+                .spacing(10)
+                .align_y(Vertical::Center),
+        )
+        .center_x(Length::Fill)
+    }
+
     pub fn view(&self) -> Column<'_, Message> {
-        Column::from_vec(vec![
-            self.view_filter().into(),
-            self.view_menu().into(),
-            self.view_power().into(),
-        ])
-        .align_x(Horizontal::Center)
-        .padding(self.config.padding)
-        .width(Length::Fill)
-        .height(Length::Fill)
+        let mut parts: Vec<Element<Message>> = vec![self.view_filter().into()];
+        if !self.levels.is_empty() {
+            parts.push(self.view_breadcrumb().into());
+        }
+        if self.shows_tabs() {
+            parts.push(self.view_tabs().into());
+        }
+        parts.push(self.view_menu().into());
+        parts.push(self.view_power().into());
+        Column::from_vec(parts)
+            .align_x(Horizontal::Center)
+            .padding(self.metrics.padding)
+            .width(Length::Fill)
+            .height(Length::Fill)
+    }
+}
+
+/// Categories of `entries` in order of their first entry
+fn categories(entries: &[MenuEntry]) -> Vec<Option<String>> {
+    let mut categories: Vec<Option<String>> = vec![];
+    for entry in entries {
+        if !categories.contains(&entry.category) {
+            categories.push(entry.category.clone());
+        }
+    }
+    categories
+}
+
+/// Columns of a carousel row with `len` entries shown when `selected` is selected
+/// at most `visible` (at least one) columns around the selected one are shown
+fn carousel_window(len: usize, selected: usize, visible: usize) -> Range<usize> {
+    let visible = visible.clamp(1, len.max(1));
+    let start = selected
+        .saturating_sub(visible / 2)
+        .min(len.saturating_sub(visible));
+    start..(start + visible).min(len)
+}
+
+/// Load the launch history, an unreadable history is reported and treated as empty
+fn load_history() -> History {
+    history_path()
+        .map(|path| {
+            History::load(&path).unwrap_or_else(|err| {
+                eprintln!("Could not load launch history:\n{err}");
+                History::default()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Load the favorites, unreadable favorites are reported and treated as empty
+fn load_favorites() -> Favorites {
+    favorites_path()
+        .map(|path| {
+            Favorites::load(&path).unwrap_or_else(|err| {
+                eprintln!("Could not load favorites:\n{err}");
+                Favorites::default()
+            })
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod state_tests {
+    use super::carousel_window;
+
+    #[test]
+    fn carousel() {
+        assert_eq!(carousel_window(10, 0, 5), 0..5);
+        assert_eq!(carousel_window(10, 5, 5), 3..8);
+        assert_eq!(carousel_window(10, 9, 5), 5..10);
+        assert_eq!(carousel_window(3, 1, 5), 0..3);
+        assert_eq!(carousel_window(10, 4, 0), 4..5);
+        assert_eq!(carousel_window(0, 0, 5), 0..0)
     }
 }
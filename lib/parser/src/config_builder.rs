@@ -1,4 +1,10 @@
-use crate::{ConfigContents, Error};
+use crate::{
+    ConfigContents, Error, KeySchema, Location, Override, Schema, SectionSchema, Unexpected,
+    diagnostics::suggest,
+};
+
+/// Name of [`Section::any`] in [`ConfigBuilder::sections`]
+pub const ANY_SECTION: &str = "*";
 
 pub struct Key {
     key: String,
@@ -8,6 +14,18 @@ pub struct Key {
 pub struct Section {
     section: String,
     optional: bool,
+    repeated: bool,
+    any: bool,
+}
+
+/// How repeated keys in a section or repeated sections are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Duplicates {
+    /// Report an error
+    #[default]
+    Error,
+    /// The last occurrence replaces earlier ones
+    LastWins,
 }
 
 impl Section {
@@ -17,6 +35,33 @@ impl Section {
         Self {
             section: sec.to_owned(),
             optional,
+            repeated: false,
+            any: false,
+        }
+    }
+
+    /// Create a section that can be given any number of times as `[[sec]]`
+    /// its values are parsed with [`ConfigBuilder::parse_indexed`]
+    #[must_use]
+    pub fn repeated(sec: &str) -> Self {
+        Self {
+            section: sec.to_owned(),
+            optional: true,
+            repeated: true,
+            any: false,
+        }
+    }
+
+    /// Create a section matching every named section that is not given by another [`Section`]
+    /// [`ConfigBuilder::section_keys`] and [`ConfigBuilder::parse_value`]
+    /// are called with the name used in the file
+    #[must_use]
+    pub fn any() -> Self {
+        Self {
+            section: ANY_SECTION.to_owned(),
+            optional: true,
+            repeated: false,
+            any: true,
         }
     }
 
@@ -26,8 +71,42 @@ impl Section {
         Self {
             section: String::new(),
             optional: false,
+            repeated: false,
+            any: false,
         }
     }
+
+    /// Name of the section, empty for the section before any header
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.section
+    }
+
+    /// Whether the section can be left out
+    #[must_use]
+    pub const fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    /// Whether the section is written as `[[section]]` and can be repeated
+    #[must_use]
+    pub const fn is_repeated(&self) -> bool {
+        self.repeated
+    }
+
+    /// Whether the section matches any named section, see [`Section::any`]
+    #[must_use]
+    pub const fn is_any(&self) -> bool {
+        self.any
+    }
+}
+
+/// Find the section matching `name`, preferring sections with that exact name over [`Section::any`]
+fn find_section<'a>(sections: &'a [Section], name: &str) -> Option<&'a Section> {
+    sections
+        .iter()
+        .find(|sec| !sec.any && sec.section == name)
+        .or_else(|| sections.iter().find(|sec| sec.any && !name.is_empty()))
 }
 
 impl Key {
@@ -39,6 +118,18 @@ impl Key {
             optional,
         }
     }
+
+    /// Name of the key
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.key
+    }
+
+    /// Whether the key can be left out
+    #[must_use]
+    pub const fn is_optional(&self) -> bool {
+        self.optional
+    }
 }
 
 /// Trait for building types from a parsed [`ConfigContents`] (see [`from_contents`])
@@ -46,7 +137,7 @@ pub trait ConfigBuilder: Default {
     /// The type built from `Self`
     type Output;
     /// The Error type if parsing fails
-    type Error: std::error::Error + From<Error>;
+    type Error: std::error::Error + Send + Sync + From<Error> + 'static;
     /// sections in [`ConfigContents`] used by `Self`
     fn sections() -> Vec<Section>;
     /// keys in a given config section used by `Self`
@@ -58,8 +149,64 @@ pub trait ConfigBuilder: Default {
     /// Returns an error if the value cannot be parsed to the required type
     /// or when either section or config are not part of `Self`
     fn parse_value(&mut self, section: &str, key: &str, value: &str) -> Result<(), Self::Error>;
+    /// Start element `index` of a repeated section, called for each `[[section]]` in order
+    /// before its values are parsed with [`Self::parse_indexed`]
+    /// # Errors
+    /// Returns an error if `section` is not a repeated section of `Self`
+    fn start_indexed(&mut self, section: &str, index: usize) -> Result<(), Self::Error> {
+        let _ = index;
+        Err(Error::unknown_section(section).into())
+    }
+    /// Parse a value of element `index` of a repeated section
+    /// # Errors
+    /// Returns an error if the value cannot be parsed to the required type
+    /// or when either section or key are not part of `Self`
+    fn parse_indexed(
+        &mut self,
+        section: &str,
+        index: usize,
+        key: &str,
+        value: &str,
+    ) -> Result<(), Self::Error> {
+        let _ = (index, key, value);
+        Err(Error::unknown_section(section).into())
+    }
     /// After adding all key-value pairs required by `Self`, build the output type
     fn build(self) -> Self::Output;
+    /// How duplicate keys and sections are handled, defaults to [`Duplicates::Error`]
+    fn duplicates() -> Duplicates {
+        Duplicates::default()
+    }
+    /// Sections and keys used by `Self`
+    /// defaults to the names in [`Self::sections`] and [`Self::section_keys`],
+    /// `#[derive(ConfigBuilder)]` adds types, defaults and descriptions
+    fn schema() -> Schema {
+        let sections = Self::sections()
+            .into_iter()
+            .map(|section| SectionSchema {
+                keys: Self::section_keys(&section.section)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|key| KeySchema {
+                        name: key.key,
+                        optional: key.optional,
+                        ..KeySchema::default()
+                    })
+                    .collect(),
+                name: section.section,
+                optional: section.optional,
+                repeated: section.repeated,
+                description: String::new(),
+            })
+            .collect();
+        Schema { sections }
+    }
+}
+
+/// Types that can be built by a [`ConfigBuilder`], implemented by `#[derive(ConfigBuilder)]`
+pub trait Configurable {
+    /// The builder with `Output = Self`
+    type Builder: ConfigBuilder<Output = Self>;
 }
 
 /// Given a [`ConfigBuilder`] and [`ConfigContents`], build [`ConfigBuilder::Output`]
@@ -70,42 +217,374 @@ pub trait ConfigBuilder: Default {
 /// - a value could not be parsed
 /// - there are remaining keys after parsing all keys in [`ConfigBuilder::section_keys`]
 /// - there are remaining sections after parsing all sections in [`ConfigBuilder::sections`]
+/// - a strict override does not match a section and key of the builder,
+///   see [`Override::strict`]
+///
+/// Overrides are applied after the values in the file and can supply missing required keys
 pub fn from_contents<Builder>(
-    mut contents: ConfigContents,
+    contents: ConfigContents,
+    overrides: &[Override],
 ) -> Result<Builder::Output, Builder::Error>
 where
     Builder: ConfigBuilder,
 {
+    let location = |span| Location::new(&contents.path, &contents.source, span);
+    let sections = Builder::sections();
     let mut builder = Builder::default();
-    for section in Builder::sections() {
-        let Some(mut config_section) = contents.sections.remove(&section.section) else {
-            if section.optional {
-                continue;
-            }
-            return Err(Error::missing_section(&contents.path, &section.section).into());
+    let mut unexpected_sections = vec![];
+    let mut parsed = vec![];
+    // sections and keys are read in order of appearance, so errors are reported in that order
+    for config_section in &contents.sections {
+        let Some(section) = find_section(&sections, &config_section.name) else {
+            unexpected_sections.push(Unexpected {
+                name: config_section.name.clone(),
+                location: location(config_section.span),
+                suggestion: suggest(
+                    &config_section.name,
+                    sections.iter().map(|sec| sec.section.as_str()),
+                ),
+            });
+            continue;
         };
-        for key in Builder::section_keys(&section.section)? {
-            let Some(value) = config_section.values.remove(&key.key) else {
-                if key.optional {
-                    continue;
-                }
-                return Err(Error::missing_key(&contents.path, &section.section, &key.key).into());
+        if section.repeated != config_section.index.is_some() {
+            let name = &config_section.name;
+            let header = if section.repeated {
+                format!("[[{name}]]")
+            } else {
+                format!("[{name}]")
             };
-            builder.parse_value(&section.section, &key.key, &value)?;
+            return Err(Error::format(
+                location(config_section.span),
+                &format!("Section {name} has to be written as {header}"),
+            )
+            .into());
+        }
+        let section_name = config_section.display_name();
+        let name = &config_section.name;
+        let keys = Builder::section_keys(name)?;
+        let unexpected: Vec<Unexpected> = config_section
+            .values
+            .iter()
+            .filter(|value| !keys.iter().any(|key| key.key == value.key))
+            .map(|value| Unexpected {
+                name: value.key.clone(),
+                location: location(value.key_span),
+                suggestion: suggest(&value.key, keys.iter().map(|key| key.key.as_str())),
+            })
+            .collect();
+        if !unexpected.is_empty() {
+            return Err(Error::unexpected_keys(&contents.path, &section_name, unexpected).into());
         }
-        if !config_section.values.is_empty() {
-            return Err(Error::unexpected_keys(
+        if let Some(index) = config_section.index {
+            builder.start_indexed(name, index)?;
+        }
+        for value in &config_section.values {
+            match config_section.index {
+                Some(index) => builder.parse_indexed(name, index, &value.key, &value.value),
+                None => builder.parse_value(name, &value.key, &value.value),
+            }
+            .map_err(|err| {
+                Error::invalid_value(location(value.value_span), &section_name, &value.key, err)
+            })?;
+        }
+        parsed.push((config_section, keys));
+    }
+    if !unexpected_sections.is_empty() {
+        return Err(Error::unexpected_sections(&contents.path, unexpected_sections).into());
+    }
+    // overrides can supply required keys and sections missing in the file
+    let mut overridden = vec![];
+    for over in overrides {
+        overridden.extend(apply_override(&mut builder, over)?);
+    }
+    let is_overridden =
+        |section: &str, key: &str| overridden.iter().any(|(sec, k)| sec == section && k == key);
+    for (config_section, keys) in parsed {
+        if let Some(key) = keys.iter().find(|key| {
+            !key.optional
+                && !config_section.contains_key(&key.key)
+                && !is_overridden(&config_section.name, &key.key)
+        }) {
+            let section_location =
+                (config_section.span.line != 0).then(|| location(config_section.span));
+            return Err(Error::missing_key(
                 &contents.path,
-                &section.section,
-                config_section.values.keys().collect(),
+                &config_section.display_name(),
+                &key.key,
+                section_location,
             )
             .into());
         }
     }
-    if !contents.sections.is_empty() {
-        return Err(
-            Error::unexpected_sections(&contents.path, contents.sections.keys().collect()).into(),
-        );
+    for section in sections
+        .iter()
+        .filter(|sec| !sec.optional && !sec.any && contents.section(&sec.section).is_none())
+    {
+        if !overridden.iter().any(|(sec, _)| *sec == section.section) {
+            return Err(Error::missing_section(&contents.path, &section.section).into());
+        }
+        if let Some(key) = Builder::section_keys(&section.section)?
+            .into_iter()
+            .find(|key| !key.optional && !is_overridden(&section.section, &key.key))
+        {
+            return Err(
+                Error::missing_key(&contents.path, &section.section, &key.key, None).into(),
+            );
+        }
     }
     Ok(builder.build())
 }
+
+/// Parse the value of an [`Override`] into the builder
+/// section and key names are matched case-insensitively.
+/// Returns the section and key that were set, `None` if a lenient override was skipped
+/// # Errors
+/// Returns an error if the section or key of a strict override are not used by the builder
+/// (see [`Override::strict`]) or if the value cannot be parsed
+fn apply_override<Builder>(
+    builder: &mut Builder,
+    over: &Override,
+) -> Result<Option<(String, String)>, Builder::Error>
+where
+    Builder: ConfigBuilder,
+{
+    let unknown = |reason: String| {
+        if over.strict {
+            Err(Error::invalid_override(&over.origin, reason).into())
+        } else {
+            eprintln!("Ignoring override {}: {reason}", over.origin);
+            Ok(None)
+        }
+    };
+    let sections = Builder::sections();
+    let Some(section) = sections
+        .iter()
+        .find(|sec| !sec.any && sec.section.eq_ignore_ascii_case(&over.section))
+        .or_else(|| find_section(&sections, &over.section))
+    else {
+        return unknown(format!("Unknown section {}", over.section));
+    };
+    if section.repeated {
+        return Err(Error::invalid_override(
+            &over.origin,
+            format!("Repeated section {} cannot be overridden", section.section),
+        )
+        .into());
+    }
+    let name = if section.any {
+        &over.section
+    } else {
+        &section.section
+    };
+    let Some(key) = Builder::section_keys(name)?
+        .into_iter()
+        .find(|key| key.key.eq_ignore_ascii_case(&over.key))
+    else {
+        return unknown(format!("Unknown key {}", over.key));
+    };
+    builder
+        .parse_value(name, &key.key, &over.value)
+        .map_err(|err| Error::invalid_override(&over.origin, err))?;
+    Ok(Some((name.clone(), key.key)))
+}
+
+#[cfg(test)]
+mod derive_tests {
+    use super::{Configurable, from_contents};
+    use crate::ConfigBuilder;
+    use crate::{Duplicates, Error, Override, contents_from_string, parse_file};
+    use std::{fs::write, path::PathBuf};
+
+    fn parse_upper(value: &str) -> Result<String, Error> {
+        Ok(value.to_uppercase())
+    }
+
+    #[derive(ConfigBuilder)]
+    #[config(error = Error)]
+    struct Inner {
+        #[config(default = 1)]
+        size: u8,
+    }
+
+    /// Test config
+    #[derive(ConfigBuilder)]
+    #[config(error = Error, builder = OuterConfigBuilder)]
+    struct Outer {
+        /// Title of the menu
+        title: String,
+        #[config(key = "font-size", default = 12.0)]
+        text_size: f32,
+        #[config(optional, parser = parse_upper)]
+        name: Option<String>,
+        /// Inner section
+        #[config(nested, section = "Inner")]
+        inner: Inner,
+        #[config(repeated, section = "Item")]
+        items: Vec<Inner>,
+        #[config(skip)]
+        skipped: bool,
+    }
+
+    #[derive(ConfigBuilder)]
+    #[config(error = Error, duplicates = "last-wins")]
+    struct Compat {
+        title: String,
+        #[config(nested, section = "Inner")]
+        inner: Inner,
+    }
+
+    fn parse(input: &str) -> Result<Outer, Error> {
+        parse_with(input, &[])
+    }
+
+    fn parse_with(input: &str, overrides: &[Override]) -> Result<Outer, Error> {
+        let contents =
+            contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error).unwrap();
+        from_contents::<<Outer as Configurable>::Builder>(contents, overrides)
+    }
+
+    #[test]
+    fn derive_defaults() {
+        let result = parse("title = menu").unwrap();
+        assert_eq!(result.title, "menu");
+        assert_eq!(result.text_size, 12.0);
+        assert_eq!(result.name, None);
+        assert_eq!(result.inner.size, 1);
+        assert!(!result.skipped)
+    }
+
+    #[test]
+    fn derive_values() {
+        let result = parse("title = menu\nfont-size = 20\nname = tv\n[Inner]\nsize = 3").unwrap();
+        assert_eq!(result.text_size, 20.0);
+        assert_eq!(result.name, Some("TV".to_owned()));
+        assert_eq!(result.inner.size, 3)
+    }
+
+    #[test]
+    fn derive_required() {
+        let result = parse("font-size = 20");
+        assert!(matches!(result, Err(Error::MissingKey { .. })))
+    }
+
+    #[test]
+    fn derive_invalid_value() {
+        let result = parse("title = menu\n[Inner]\nsize = big");
+        let Err(err @ Error::InvalidValue { .. }) = result else {
+            panic!("expected invalid value")
+        };
+        // the error of the builder is kept as source
+        let source = std::error::Error::source(&err).and_then(|source| source.downcast_ref());
+        assert!(matches!(source, Some(Error::InvalidType { .. })))
+    }
+
+    #[test]
+    fn derive_sections() {
+        let sections = OuterConfigBuilder::sections();
+        let names: Vec<&str> = sections.iter().map(|sec| sec.name()).collect();
+        assert_eq!(names, vec!["", "Inner", "Item"]);
+        assert!(!sections[0].is_optional());
+        assert!(sections[1].is_optional());
+        assert!(sections[2].is_repeated())
+    }
+
+    #[test]
+    fn derive_schema() {
+        let schema = OuterConfigBuilder::schema();
+        let top = &schema.sections[0];
+        assert_eq!(top.description, "Test config");
+        assert_eq!(top.keys[0].name, "title");
+        assert_eq!(top.keys[0].description, "Title of the menu");
+        assert_eq!(top.keys[0].default, None);
+        assert!(!top.keys[0].optional);
+        assert_eq!(top.keys[1].value_type, "number (f32)");
+        assert_eq!(top.keys[1].default, Some("12".to_owned()));
+        let inner = &schema.sections[1];
+        assert_eq!(inner.name, "Inner");
+        assert_eq!(inner.description, "Inner section");
+        assert_eq!(inner.keys[0].default, Some("1".to_owned()))
+    }
+
+    #[test]
+    fn unexpected_keys_in_order() {
+        let result = parse("title = menu\nzeta = 1\nalpha = 2\nmid = 3");
+        let Err(Error::UnexpectedKeys { keys, .. }) = result else {
+            panic!("expected unexpected keys")
+        };
+        let names: Vec<&str> = keys.iter().map(|key| key.name.as_str()).collect();
+        assert_eq!(names, vec!["zeta", "alpha", "mid"])
+    }
+
+    #[test]
+    fn unexpected_keys_display() {
+        let result = parse("title = menu\n  font-szie = 20").err().unwrap();
+        assert_eq!(
+            result.to_string(),
+            "Unexpected keys font-szie in test.conf\n \
+             --> test.conf:2:3\n  \
+             |\n\
+             2 |   font-szie = 20\n  \
+             |   ^^^^^^^^^\n  \
+             = help: did you mean `font-size`?\n"
+        )
+    }
+
+    #[test]
+    fn derive_repeated() {
+        let result =
+            parse("title = menu\n[[Item]]\nsize = 2\n[[Item]]\n[[Item]]\nsize = 4").unwrap();
+        let sizes: Vec<u8> = result.items.iter().map(|item| item.size).collect();
+        assert_eq!(sizes, vec![2, 1, 4]);
+        assert!(parse("title = menu").unwrap().items.is_empty())
+    }
+
+    #[test]
+    fn repeated_invalid_value() {
+        let result = parse("title = menu\n[[Item]]\nsize = 2\n[[Item]]\nsize = big");
+        let Err(Error::InvalidValue {
+            section, location, ..
+        }) = result
+        else {
+            panic!("expected invalid value")
+        };
+        assert_eq!(section, "Item[1]");
+        assert_eq!(location.span.line, 5)
+    }
+
+    #[test]
+    fn repeated_written_as_section() {
+        let result = parse("title = menu\n[Item]\nsize = 2");
+        assert!(matches!(result, Err(Error::InvalidFormat { .. })))
+    }
+
+    #[test]
+    fn overrides() {
+        let set = |arg| Override::from_arg(arg).unwrap();
+        let env = |name, value| Override::from_env_var("TVMENU_", name, value).unwrap();
+        // a required key can be given by an override only
+        let result = parse_with("font-size = 20", &[set("title=menu")]).unwrap();
+        assert_eq!(result.title, "menu");
+        let result = parse_with("", &[env("TVMENU_TITLE", "menu"), set("Inner.size=4")]).unwrap();
+        assert_eq!(result.inner.size, 4);
+        // unknown environment variables are skipped, unknown --set values are errors
+        let result = parse_with("title = menu", &[env("TVMENU_DEBUG", "1")]).unwrap();
+        assert_eq!(result.title, "menu");
+        let result = parse_with("title = menu", &[set("debug=1")]);
+        assert!(matches!(result, Err(Error::InvalidOverride { .. })));
+        let result = parse_with("title = menu", &[env("TVMENU_INNER__SIZE", "big")]);
+        assert!(matches!(result, Err(Error::InvalidOverride { .. })))
+    }
+
+    #[test]
+    fn duplicates_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.conf");
+        let source = "title = old\ntitle = new\n[Inner]\nsize = 2\n[Inner]\nsize = 3\n";
+        write(&path, source).unwrap();
+        let result = parse_file::<<Compat as Configurable>::Builder>(path.clone()).unwrap();
+        assert_eq!(result.title, "new");
+        assert_eq!(result.inner.size, 3);
+        let result = parse_file::<<Outer as Configurable>::Builder>(path);
+        assert!(matches!(result, Err(Error::DuplicateKey { .. })))
+    }
+}
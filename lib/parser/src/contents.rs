@@ -1,10 +1,75 @@
-use std::{collections::HashMap, path::PathBuf};
+use crate::Span;
+use std::path::PathBuf;
 
+pub struct ConfigValue {
+    pub(crate) key: String,
+    pub(crate) value: String,
+    /// location of the key
+    pub(crate) key_span: Span,
+    /// location of the (raw) value
+    pub(crate) value_span: Span,
+    /// whether the value was quoted, quoted values are not interpolated
+    pub(crate) literal: bool,
+}
+
+/// A section with its values in order of appearance
 pub struct ConfigSection {
-    pub(crate) values: HashMap<String, String>,
+    pub(crate) name: String,
+    /// position among the sections called `name` for repeated sections (`[[name]]`)
+    pub(crate) index: Option<usize>,
+    pub(crate) values: Vec<ConfigValue>,
+    /// location of the section header, [`Span::default`] for the empty section
+    pub(crate) span: Span,
 }
 
+/// Parsed config file, sections are kept in order of appearance
 pub struct ConfigContents {
-    pub(crate) sections: HashMap<String, ConfigSection>,
+    pub(crate) sections: Vec<ConfigSection>,
     pub(crate) path: PathBuf,
+    /// the parsed input, used to show errors
+    pub(crate) source: String,
+}
+
+impl ConfigSection {
+    /// Name used in errors, with the index for repeated sections
+    pub(crate) fn display_name(&self) -> String {
+        match self.index {
+            Some(index) => format!("{}[{index}]", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&ConfigValue> {
+        self.values.iter().find(|value| value.key == key)
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &str) -> Option<&mut ConfigValue> {
+        self.values.iter_mut().find(|value| value.key == key)
+    }
+
+    pub(crate) fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Remove the value of `key`, keeping the order of the remaining values
+    pub(crate) fn remove(&mut self, key: &str) -> Option<ConfigValue> {
+        let ind = self.values.iter().position(|value| value.key == key)?;
+        Some(self.values.remove(ind))
+    }
+}
+
+impl ConfigContents {
+    /// The first section called `name`
+    pub(crate) fn section(&self, name: &str) -> Option<&ConfigSection> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Remove the first section called `name`, keeping the order of the remaining sections
+    pub(crate) fn remove_section(&mut self, name: &str) -> Option<ConfigSection> {
+        let ind = self
+            .sections
+            .iter()
+            .position(|section| section.name == name)?;
+        Some(self.sections.remove(ind))
+    }
 }
@@ -0,0 +1,181 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Location of a section header, key or value in a file
+/// line and column start at 1, a line of 0 means there is no location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    /// length in characters
+    pub len: usize,
+}
+
+impl Span {
+    /// Create the span of `token` inside `line`, where `token` is a subslice of `line`
+    #[must_use]
+    pub(crate) fn of(line_nr: usize, line: &str, token: &str) -> Self {
+        let start = line.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize).wrapping_sub(start);
+        debug_assert!(
+            offset <= line.len() && offset + token.len() <= line.len(),
+            "token is not a subslice of line"
+        );
+        Self {
+            line: line_nr,
+            column: line
+                .get(..offset)
+                .map_or(0, |before| before.chars().count())
+                + 1,
+            len: token.chars().count(),
+        }
+    }
+
+    /// Span covering the whole line
+    #[must_use]
+    pub fn line(line_nr: usize, line: &str) -> Self {
+        Self::of(line_nr, line, line)
+    }
+}
+
+/// A [`Span`] together with the file and the source line it points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub span: Span,
+    /// The full line the span points to
+    pub source_line: String,
+}
+
+impl Location {
+    /// Create a location from the full `source` of a file
+    #[must_use]
+    pub fn new(path: &Path, source: &str, span: Span) -> Self {
+        let source_line = span
+            .line
+            .checked_sub(1)
+            .and_then(|ind| source.lines().nth(ind))
+            .unwrap_or_default()
+            .to_owned();
+        Self {
+            path: path.to_path_buf(),
+            span,
+            source_line,
+        }
+    }
+
+    /// Render the location compiler-style, with the source line and a caret below the span
+    /// # Errors
+    /// Returns an error if writing to `f` fails
+    pub fn render(&self, f: &mut fmt::Formatter, help: Option<&str>) -> fmt::Result {
+        if self.span.line == 0 {
+            return writeln!(f, " --> {}", self.path.display());
+        }
+        let line_nr = self.span.line.to_string();
+        let gutter = " ".repeat(line_nr.len());
+        writeln!(
+            f,
+            "{gutter}--> {}:{}:{}",
+            self.path.display(),
+            self.span.line,
+            self.span.column
+        )?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line_nr} | {}", self.source_line)?;
+        writeln!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.span.column.saturating_sub(1)),
+            "^".repeat(self.span.len.max(1))
+        )?;
+        if let Some(help) = help {
+            writeln!(f, "{gutter} = help: {help}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A key or section that is not used by a [`crate::ConfigBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unexpected {
+    pub name: String,
+    pub location: Location,
+    /// A similar name that is used, if any
+    pub suggestion: Option<String>,
+}
+
+impl Unexpected {
+    /// Render the location, suggesting [`Self::suggestion`] if there is one
+    /// # Errors
+    /// Returns an error if writing to `f` fails
+    pub fn render(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let help = self
+            .suggestion
+            .as_ref()
+            .map(|suggestion| format!("did you mean `{suggestion}`?"));
+        self.location.render(f, help.as_deref())
+    }
+}
+
+/// Find the candidate closest to `name`, if it is close enough to be a likely misspelling
+#[must_use]
+pub fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_owned())
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(first: &str, second: &str) -> usize {
+    let second: Vec<char> = second.chars().collect();
+    let mut prev_row: Vec<usize> = (0..=second.len()).collect();
+    for (ind, ch) in first.chars().enumerate() {
+        let mut row = vec![ind + 1];
+        for (ind2, ch2) in second.iter().enumerate() {
+            let cost = usize::from(ch != *ch2);
+            let value = (prev_row[ind2] + cost)
+                .min(prev_row[ind2 + 1] + 1)
+                .min(row[ind2] + 1);
+            row.push(value);
+        }
+        prev_row = row;
+    }
+    prev_row[second.len()]
+}
+
+#[cfg(test)]
+mod diagnostics_tests {
+    use super::{Span, suggest};
+
+    #[test]
+    fn span_of() {
+        let line = "  text-size = 26";
+        let result = Span::of(3, line, &line[14..]);
+        assert_eq!(
+            result,
+            Span {
+                line: 3,
+                column: 15,
+                len: 2
+            }
+        )
+    }
+
+    #[test]
+    fn suggest_typo() {
+        let result = suggest("text-szie", ["text-size", "text-color", "padding"]);
+        assert_eq!(result, Some("text-size".to_owned()))
+    }
+
+    #[test]
+    fn suggest_none() {
+        let result = suggest("launcher", ["title", "icon"]);
+        assert_eq!(result, None)
+    }
+}
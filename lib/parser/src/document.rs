@@ -0,0 +1,338 @@
+use crate::{
+    Error,
+    values::{format_value, read_value, remove_comment},
+};
+use std::{
+    fmt,
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+/// Types that can be written to a [`ConfigDocument`], implemented by `#[derive(ConfigBuilder)]`
+pub trait ToConfig {
+    /// Append all keys of `self` to `document`, each preceded by its description
+    fn write_config(&self, document: &mut ConfigDocument);
+
+    /// A new document containing all keys of `self`
+    fn to_document(&self) -> ConfigDocument {
+        let mut document = ConfigDocument::new();
+        self.write_config(&mut document);
+        document
+    }
+}
+
+/// A line (or several lines for continued values) of a [`ConfigDocument`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Line {
+    /// Blank lines, comments and anything else that is kept as is
+    Text(String),
+    /// A section header
+    Section { name: String, raw: String },
+    /// A key-value pair in `section`
+    Entry {
+        section: String,
+        key: String,
+        raw: String,
+    },
+}
+
+/// A config file that can be edited and written back
+///
+/// Comments, blank lines and the order of keys and sections are kept,
+/// only lines of changed keys are rewritten.
+/// Values are written as given, so a literal `$` needs to be written as `$$`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDocument {
+    lines: Vec<Line>,
+    trailing_newline: bool,
+}
+
+impl ConfigDocument {
+    /// Create an empty document
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            lines: vec![],
+            trailing_newline: true,
+        }
+    }
+
+    /// Read a document from a file
+    /// # Errors
+    /// Returns an error if the file cannot be read
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let source = read_to_string(path).map_err(|err| Error::read_file(&err, path))?;
+        Ok(Self::parse(&source))
+    }
+
+    /// Split `source` into lines, lines that are not sections or key-value pairs are kept as text
+    #[must_use]
+    pub fn parse(source: &str) -> Self {
+        let mut lines = vec![];
+        let mut section = String::new();
+        let raw_lines: Vec<&str> = source.lines().collect();
+        let mut num = 0;
+        while let Some(raw_line) = raw_lines.get(num) {
+            let line = remove_comment(raw_line).trim();
+            num += 1;
+            if line.starts_with('[') && line.ends_with(']') {
+                section = line.replace(['[', ']'], "");
+                lines.push(Line::Section {
+                    name: section.clone(),
+                    raw: (*raw_line).to_owned(),
+                });
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                lines.push(Line::Text((*raw_line).to_owned()));
+                continue;
+            };
+            // continued and multi-line values belong to the entry
+            let mut rest = raw_lines[num..].iter().copied().enumerate();
+            if read_value(value.trim(), &mut rest).is_err() {
+                lines.push(Line::Text((*raw_line).to_owned()));
+                continue;
+            }
+            let start = num - 1;
+            num = rest.next().map_or(raw_lines.len(), |(ind, _)| num + ind);
+            lines.push(Line::Entry {
+                section: section.clone(),
+                key: key.trim().to_owned(),
+                raw: raw_lines[start..num].join("\n"),
+            });
+        }
+        Self {
+            lines,
+            trailing_newline: source.is_empty() || source.ends_with('\n'),
+        }
+    }
+
+    /// Whether the document has no lines
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Whether `section` contains `key`
+    #[must_use]
+    pub fn contains(&self, section: &str, key: &str) -> bool {
+        self.find_entry(section, key).is_some()
+    }
+
+    /// Set the value of `key` in `section`
+    ///
+    /// An existing key is replaced in place, keeping its indentation and trailing comment,
+    /// otherwise the key is added after the last key of the section.
+    /// Missing sections are added at the end of the document.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        let value = format_value(value);
+        if let Some(ind) = self.find_entry(section, key)
+            && let Line::Entry { raw, .. } = &mut self.lines[ind]
+        {
+            let indent = &raw[..raw.len() - raw.trim_start().len()];
+            let comment = if raw.contains('\n') {
+                ""
+            } else {
+                &raw[remove_comment(raw).trim_end().len()..]
+            };
+            *raw = format!("{indent}{key} = {value}{comment}");
+            return;
+        }
+        let entry = Line::Entry {
+            section: section.to_owned(),
+            key: key.to_owned(),
+            raw: format!("{key} = {value}"),
+        };
+        let position = self.insert_position(section);
+        self.lines.insert(position, entry);
+    }
+
+    /// Remove `key` from `section`, returns `false` if the key was not found
+    pub fn remove(&mut self, section: &str, key: &str) -> bool {
+        self.find_entry(section, key)
+            .map(|ind| self.lines.remove(ind))
+            .is_some()
+    }
+
+    /// Append a section header
+    pub fn push_section(&mut self, section: &str) {
+        self.lines.push(Line::Section {
+            name: section.to_owned(),
+            raw: format!("[{section}]"),
+        });
+    }
+
+    /// Append the header of a new element of a repeated section, `[[section]]`
+    pub fn push_repeated_section(&mut self, section: &str) {
+        self.lines.push(Line::Section {
+            name: section.to_owned(),
+            raw: format!("[[{section}]]"),
+        });
+    }
+
+    /// Append a key-value pair to the last section
+    pub fn push_value(&mut self, key: &str, value: &str) {
+        let section = self.last_section().to_owned();
+        self.lines.push(Line::Entry {
+            section,
+            key: key.to_owned(),
+            raw: format!("{key} = {}", format_value(value)),
+        });
+    }
+
+    /// Append a comment, with a `// ` prefix for each line of `comment`
+    pub fn push_comment(&mut self, comment: &str) {
+        for line in comment.lines() {
+            self.lines
+                .push(Line::Text(format!("// {line}").trim_end().to_owned()));
+        }
+    }
+
+    /// Append an empty line
+    pub fn push_blank(&mut self) {
+        self.lines.push(Line::Text(String::new()));
+    }
+
+    /// Write the document to a file
+    /// # Errors
+    /// Returns an error if the file cannot be written
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        write(path, self.to_string()).map_err(|err| Error::write_file(&err, path))
+    }
+
+    fn find_entry(&self, section: &str, key: &str) -> Option<usize> {
+        self.lines.iter().position(|line| {
+            matches!(line, Line::Entry { section: sec, key: k, .. } if sec == section && k == key)
+        })
+    }
+
+    fn last_section(&self) -> &str {
+        self.lines
+            .iter()
+            .rev()
+            .find_map(|line| match line {
+                Line::Section { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Position for a new key in `section`, adds the section if it does not exist
+    fn insert_position(&mut self, section: &str) -> usize {
+        let last_entry = self
+            .lines
+            .iter()
+            .rposition(|line| matches!(line, Line::Entry { section: sec, .. } if sec == section));
+        if let Some(ind) = last_entry {
+            return ind + 1;
+        }
+        if section.is_empty() {
+            return self.empty_section_end();
+        }
+        let header = self
+            .lines
+            .iter()
+            .position(|line| matches!(line, Line::Section { name, .. } if name == section));
+        if let Some(ind) = header {
+            return ind + 1;
+        }
+        if !self.lines.is_empty() {
+            self.push_blank();
+        }
+        self.push_section(section);
+        self.lines.len()
+    }
+
+    /// End of the empty section, that is before the first header and the blank lines before it
+    fn empty_section_end(&self) -> usize {
+        let Some(first_header) = self
+            .lines
+            .iter()
+            .position(|line| matches!(line, Line::Section { .. }))
+        else {
+            return self.lines.len();
+        };
+        let blank = self.lines[..first_header]
+            .iter()
+            .rev()
+            .take_while(|line| matches!(line, Line::Text(text) if text.trim().is_empty()))
+            .count();
+        first_header - blank
+    }
+}
+
+impl fmt::Display for ConfigDocument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines: Vec<&str> = self
+            .lines
+            .iter()
+            .map(|line| match line {
+                Line::Text(raw) | Line::Section { raw, .. } | Line::Entry { raw, .. } => {
+                    raw.as_str()
+                }
+            })
+            .collect();
+        write!(f, "{}", lines.join("\n"))?;
+        if self.trailing_newline && !lines.is_empty() {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod document_tests {
+    use super::ConfigDocument;
+
+    const SOURCE: &str = "// tvmenu config
+background = #000000 // black
+
+padding = 10
+launch = mpv \\
+  --fs
+
+[Entries]
+  width = 200
+";
+
+    #[test]
+    fn round_trip() {
+        let result = ConfigDocument::parse(SOURCE).to_string();
+        assert_eq!(result, SOURCE)
+    }
+
+    #[test]
+    fn set_existing() {
+        let mut doc = ConfigDocument::parse(SOURCE);
+        doc.set("", "background", "#ffffff");
+        doc.set("Entries", "width", "300");
+        doc.set("", "launch", "kodi");
+        let result = doc.to_string();
+        let expected = SOURCE
+            .replace("#000000", "#ffffff")
+            .replace("200", "300")
+            .replace("mpv \\\n  --fs", "kodi");
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn set_new() {
+        let mut doc = ConfigDocument::parse(SOURCE);
+        doc.set("", "columns", "4");
+        doc.set("Entries", "height", "300");
+        doc.set("Power", "enabled", "true");
+        let result = doc.to_string();
+        let expected = SOURCE.replace("  --fs\n", "  --fs\ncolumns = 4\n")
+            + "height = 300\n\n[Power]\nenabled = true\n";
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn remove() {
+        let mut doc = ConfigDocument::parse(SOURCE);
+        assert!(doc.remove("", "padding"));
+        assert!(!doc.remove("Entries", "padding"));
+        let result = doc.to_string();
+        assert_eq!(result, SOURCE.replace("padding = 10\n", ""))
+    }
+}
@@ -1,23 +1,32 @@
+use crate::{Location, Unexpected};
 use std::{
     fmt, io,
     path::{Path, PathBuf},
 };
 
+/// Error of a [`crate::ConfigBuilder`] or a message, kept as the source of another error
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
 #[derive(Debug)]
 pub enum Error {
     ReadFile {
         path: PathBuf,
         reason: String,
     },
-    InvalidFormat {
+    WriteFile {
         path: PathBuf,
-        line_nr: usize,
+        reason: String,
+    },
+    InvalidFormat {
+        location: Box<Location>,
         reason: String,
     },
     MissingKey {
         path: PathBuf,
         section: String,
         key: String,
+        /// location of the section header, if the section has one
+        location: Option<Box<Location>>,
     },
     MissingSection {
         path: PathBuf,
@@ -26,11 +35,51 @@ pub enum Error {
     UnexpectedKeys {
         path: PathBuf,
         section: String,
-        keys: Vec<String>,
+        keys: Vec<Unexpected>,
     },
     UnexpectedSections {
         path: PathBuf,
-        sections: Vec<String>,
+        sections: Vec<Unexpected>,
+    },
+    InvalidValue {
+        location: Box<Location>,
+        section: String,
+        key: String,
+        /// error of the builder, see [`std::error::Error::source`]
+        reason: BoxedError,
+    },
+    InvalidType {
+        value: String,
+        expected: String,
+    },
+    UnknownSection(String),
+    UnknownKey {
+        section: String,
+        key: String,
+    },
+    InvalidOverride {
+        origin: String,
+        /// error of the builder or a message, see [`std::error::Error::source`]
+        reason: BoxedError,
+    },
+    UndefinedVariable {
+        location: Box<Location>,
+        name: String,
+    },
+    VariableCycle {
+        location: Box<Location>,
+        cycle: Vec<String>,
+    },
+    DuplicateKey {
+        section: String,
+        key: String,
+        first: Box<Location>,
+        second: Box<Location>,
+    },
+    DuplicateSection {
+        section: String,
+        first: Box<Location>,
+        second: Box<Location>,
     },
 }
 
@@ -44,20 +93,28 @@ impl Error {
     }
 
     #[must_use]
-    pub fn format(path: &Path, line_nr: usize, reason: &str) -> Self {
-        Self::InvalidFormat {
+    pub fn write_file(err: &io::Error, path: &Path) -> Self {
+        Self::WriteFile {
             path: path.to_path_buf(),
-            line_nr,
+            reason: err.to_string(),
+        }
+    }
+
+    #[must_use]
+    pub fn format(location: Location, reason: &str) -> Self {
+        Self::InvalidFormat {
+            location: Box::new(location),
             reason: reason.to_owned(),
         }
     }
 
     #[must_use]
-    pub fn missing_key(path: &Path, section: &str, key: &str) -> Self {
+    pub fn missing_key(path: &Path, section: &str, key: &str, location: Option<Location>) -> Self {
         Self::MissingKey {
             path: path.to_path_buf(),
             section: section.to_owned(),
             key: key.to_owned(),
+            location: location.map(Box::new),
         }
     }
 
@@ -70,19 +127,98 @@ impl Error {
     }
 
     #[must_use]
-    pub fn unexpected_keys(path: &Path, section: &str, keys: Vec<&String>) -> Self {
+    pub fn unexpected_keys(path: &Path, section: &str, keys: Vec<Unexpected>) -> Self {
         Self::UnexpectedKeys {
             path: path.to_path_buf(),
             section: section.to_owned(),
-            keys: keys.into_iter().cloned().collect(),
+            keys,
         }
     }
 
     #[must_use]
-    pub fn unexpected_sections(path: &Path, sections: Vec<&String>) -> Self {
+    pub fn unexpected_sections(path: &Path, sections: Vec<Unexpected>) -> Self {
         Self::UnexpectedSections {
             path: path.to_path_buf(),
-            sections: sections.into_iter().cloned().collect(),
+            sections,
+        }
+    }
+
+    #[must_use]
+    pub fn invalid_value(
+        location: Location,
+        section: &str,
+        key: &str,
+        reason: impl Into<BoxedError>,
+    ) -> Self {
+        Self::InvalidValue {
+            location: Box::new(location),
+            section: section.to_owned(),
+            key: key.to_owned(),
+            reason: reason.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn invalid_type(value: &str, expected: &str) -> Self {
+        Self::InvalidType {
+            value: value.to_owned(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    #[must_use]
+    pub fn unknown_section(section: &str) -> Self {
+        Self::UnknownSection(section.to_owned())
+    }
+
+    #[must_use]
+    pub fn unknown_key(section: &str, key: &str) -> Self {
+        Self::UnknownKey {
+            section: section.to_owned(),
+            key: key.to_owned(),
+        }
+    }
+
+    #[must_use]
+    pub fn invalid_override(origin: &str, reason: impl Into<BoxedError>) -> Self {
+        Self::InvalidOverride {
+            origin: origin.to_owned(),
+            reason: reason.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn undefined_variable(location: Location, name: &str) -> Self {
+        Self::UndefinedVariable {
+            location: Box::new(location),
+            name: name.to_owned(),
+        }
+    }
+
+    #[must_use]
+    pub fn variable_cycle(location: Location, cycle: Vec<String>) -> Self {
+        Self::VariableCycle {
+            location: Box::new(location),
+            cycle,
+        }
+    }
+
+    #[must_use]
+    pub fn duplicate_key(section: &str, key: &str, first: Location, second: Location) -> Self {
+        Self::DuplicateKey {
+            section: section.to_owned(),
+            key: key.to_owned(),
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    #[must_use]
+    pub fn duplicate_section(section: &str, first: Location, second: Location) -> Self {
+        Self::DuplicateSection {
+            section: section.to_owned(),
+            first: Box::new(first),
+            second: Box::new(second),
         }
     }
 }
@@ -101,24 +237,28 @@ impl fmt::Display for Error {
             Self::ReadFile { path, reason } => {
                 write!(f, "Could not read file {}:\n{reason}", path.display())
             }
-            Self::InvalidFormat {
+            Self::WriteFile { path, reason } => {
+                write!(f, "Could not write file {}:\n{reason}", path.display())
+            }
+            Self::InvalidFormat { location, reason } => {
+                writeln!(f, "Could not parse line: {reason}")?;
+                location.render(f, None)
+            }
+            Self::MissingKey {
                 path,
-                line_nr,
-                reason,
+                section,
+                key,
+                location,
             } => {
-                write!(
-                    f,
-                    "Could not parse line {line_nr} of {}: {reason}",
-                    path.display()
-                )
-            }
-            Self::MissingKey { path, section, key } => {
-                write!(
+                writeln!(
                     f,
                     "Missing key {key}{} in {}",
                     format_section(section),
                     path.display()
-                )
+                )?;
+                location
+                    .as_ref()
+                    .map_or(Ok(()), |location| location.render(f, None))
             }
             Self::MissingSection { path, section } => {
                 write!(f, "Missing section {section} in {}", path.display())
@@ -128,24 +268,92 @@ impl fmt::Display for Error {
                 section,
                 keys,
             } => {
-                write!(
+                writeln!(
                     f,
                     "Unexpected keys {}{} in {}",
-                    keys.join(", "),
+                    keys.iter()
+                        .map(|key| key.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
                     format_section(section),
                     path.display()
-                )
+                )?;
+                keys.iter().try_for_each(|key| key.render(f))
             }
             Self::UnexpectedSections { path, sections } => {
-                write!(
+                writeln!(
                     f,
-                    "Unexpected sections {} in path {}",
+                    "Unexpected sections {} in {}",
+                    sections
+                        .iter()
+                        .map(|sec| sec.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
                     path.display(),
-                    sections.join(", ")
-                )
+                )?;
+                sections.iter().try_for_each(|sec| sec.render(f))
+            }
+            Self::InvalidValue {
+                location,
+                section,
+                key,
+                reason,
+            } => {
+                writeln!(
+                    f,
+                    "Invalid value for key {key}{}: {reason}",
+                    format_section(section)
+                )?;
+                location.render(f, None)
+            }
+            Self::InvalidType { value, expected } => {
+                write!(f, "Expected {expected}, got {value}")
+            }
+            Self::UnknownSection(section) => write!(f, "Unknown section {section}"),
+            Self::UnknownKey { section, key } => {
+                write!(f, "Unknown key {key}{}", format_section(section))
+            }
+            Self::InvalidOverride { origin, reason } => {
+                write!(f, "Invalid override {origin}: {reason}")
+            }
+            Self::UndefinedVariable { location, name } => {
+                writeln!(f, "Undefined variable {name}")?;
+                location.render(f, None)
+            }
+            Self::VariableCycle { location, cycle } => {
+                writeln!(f, "Cyclic variable reference {}", cycle.join(" -> "))?;
+                location.render(f, None)
+            }
+            Self::DuplicateKey {
+                section,
+                key,
+                first,
+                second,
+            } => {
+                writeln!(f, "Duplicate key {key}{}", format_section(section))?;
+                second.render(f, None)?;
+                first.render(f, Some("first defined here"))
+            }
+            Self::DuplicateSection {
+                section,
+                first,
+                second,
+            } => {
+                writeln!(f, "Duplicate section {section}")?;
+                second.render(f, None)?;
+                first.render(f, Some("first defined here"))
             }
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidValue { reason, .. } | Self::InvalidOverride { reason, .. } => {
+                Some(reason.as_ref())
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::Error;
+use std::{env::home_dir, path::PathBuf, time::Duration};
+
+/// Types that can be parsed from a single config value
+///
+/// Use `#[derive(FromConfigValue)]` for enums without fields,
+/// variants are matched case-insensitively by their kebab-case name
+pub trait FromConfigValue: Sized {
+    /// Description of the expected value, used in error messages
+    fn expected() -> String;
+    /// Parse a value
+    /// # Errors
+    /// Returns [`Error::InvalidType`] if the value cannot be parsed
+    fn from_config_value(value: &str) -> Result<Self, Error>;
+}
+
+/// Types that can be written as a config value, the inverse of [`FromConfigValue`]
+///
+/// Use `#[derive(ToConfigValue)]` for enums without fields
+pub trait ToConfigValue {
+    /// The value as it is written in a config file (before quoting)
+    fn to_config_value(&self) -> String;
+}
+
+/// Create the error for a value that does not match `T`
+fn invalid<T>(value: &str) -> Error
+where
+    T: FromConfigValue,
+{
+    Error::invalid_type(value, &T::expected())
+}
+
+macro_rules! from_str_impl {
+    ($expected:literal, $($ty:ty),*) => {
+        $(
+            impl FromConfigValue for $ty {
+                fn expected() -> String {
+                    format!("{} ({})", $expected, stringify!($ty))
+                }
+
+                fn from_config_value(value: &str) -> Result<Self, Error> {
+                    value.parse().map_err(|_| invalid::<Self>(value))
+                }
+            }
+
+            impl ToConfigValue for $ty {
+                fn to_config_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+from_str_impl!(
+    "integer", u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+from_str_impl!("number", f32, f64);
+
+impl FromConfigValue for bool {
+    fn expected() -> String {
+        "boolean (true or false)".to_owned()
+    }
+
+    fn from_config_value(value: &str) -> Result<Self, Error> {
+        match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(true),
+            "false" | "no" | "off" | "0" => Ok(false),
+            _ => Err(invalid::<Self>(value)),
+        }
+    }
+}
+
+impl ToConfigValue for bool {
+    fn to_config_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FromConfigValue for String {
+    fn expected() -> String {
+        "text".to_owned()
+    }
+
+    fn from_config_value(value: &str) -> Result<Self, Error> {
+        Ok(value.to_owned())
+    }
+}
+
+impl ToConfigValue for String {
+    fn to_config_value(&self) -> String {
+        self.clone()
+    }
+}
+
+impl FromConfigValue for PathBuf {
+    fn expected() -> String {
+        "path".to_owned()
+    }
+
+    /// Paths starting with `~/` are relative to the home directory
+    fn from_config_value(value: &str) -> Result<Self, Error> {
+        if value.is_empty() {
+            return Err(invalid::<Self>(value));
+        }
+        match value.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => home_dir()
+                .map(|home| home.join(rest.trim_start_matches('/')))
+                .ok_or_else(|| Error::invalid_type(value, "path, could not get home directory")),
+            _ => Ok(Self::from(value)),
+        }
+    }
+}
+
+impl ToConfigValue for PathBuf {
+    fn to_config_value(&self) -> String {
+        self.display().to_string()
+    }
+}
+
+impl FromConfigValue for Duration {
+    fn expected() -> String {
+        "duration (e.g. 500ms, 2s, 5m or 1h)".to_owned()
+    }
+
+    /// Numbers without unit are seconds
+    fn from_config_value(value: &str) -> Result<Self, Error> {
+        let number_end = value
+            .find(|ch: char| !(ch.is_ascii_digit() || ch == '.'))
+            .unwrap_or(value.len());
+        let (number, unit) = value.split_at(number_end);
+        let number: f64 = number.parse().map_err(|_| invalid::<Self>(value))?;
+        let factor = match unit.trim() {
+            "ms" => 0.001,
+            "" | "s" => 1.0,
+            "m" | "min" => 60.0,
+            "h" => 3600.0,
+            _ => return Err(invalid::<Self>(value)),
+        };
+        Self::try_from_secs_f64(number * factor).map_err(|_| invalid::<Self>(value))
+    }
+}
+
+impl ToConfigValue for Duration {
+    fn to_config_value(&self) -> String {
+        let millis = self.as_millis();
+        if millis.is_multiple_of(1000) {
+            format!("{}s", millis / 1000)
+        } else {
+            format!("{millis}ms")
+        }
+    }
+}
+
+impl<T> FromConfigValue for Vec<T>
+where
+    T: FromConfigValue,
+{
+    fn expected() -> String {
+        format!("comma separated list of {}", T::expected())
+    }
+
+    fn from_config_value(value: &str) -> Result<Self, Error> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(T::from_config_value)
+            .collect()
+    }
+}
+
+impl<T> ToConfigValue for Vec<T>
+where
+    T: ToConfigValue,
+{
+    fn to_config_value(&self) -> String {
+        self.iter()
+            .map(ToConfigValue::to_config_value)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod from_value_tests {
+    use super::{FromConfigValue, ToConfigValue};
+    use crate::Error;
+    use std::time::Duration;
+
+    #[derive(crate::FromConfigValue, crate::ToConfigValue, Debug, PartialEq, Eq)]
+    enum Layout {
+        Grid,
+        WideList,
+        #[config(name = "round")]
+        Carousel,
+    }
+
+    #[test]
+    fn parse_integer() {
+        assert_eq!(u8::from_config_value("42").unwrap(), 42);
+        let result = u8::from_config_value("300").unwrap_err();
+        assert_eq!(result.to_string(), "Expected integer (u8), got 300")
+    }
+
+    #[test]
+    fn parse_bool() {
+        assert!(bool::from_config_value("Yes").unwrap());
+        assert!(!bool::from_config_value("off").unwrap());
+        assert!(bool::from_config_value("maybe").is_err())
+    }
+
+    #[test]
+    fn parse_duration() {
+        let result = Duration::from_config_value("500ms").unwrap();
+        assert_eq!(result, Duration::from_millis(500));
+        let result = Duration::from_config_value("2").unwrap();
+        assert_eq!(result, Duration::from_secs(2));
+        assert!(Duration::from_config_value("2 days").is_err());
+        assert_eq!(Duration::from_millis(1500).to_config_value(), "1500ms")
+    }
+
+    #[test]
+    fn parse_list() {
+        let result = Vec::<f32>::from_config_value("1, 2.5,").unwrap();
+        assert_eq!(result, vec![1.0, 2.5]);
+        assert_eq!(result.to_config_value(), "1, 2.5");
+        assert!(matches!(
+            Vec::<f32>::from_config_value("1, a"),
+            Err(Error::InvalidType { .. })
+        ))
+    }
+
+    #[test]
+    fn parse_enum() {
+        assert_eq!(Layout::from_config_value("grid").unwrap(), Layout::Grid);
+        let result = Layout::from_config_value("Wide-List").unwrap();
+        assert_eq!(result, Layout::WideList);
+        assert_eq!(
+            Layout::from_config_value("round").unwrap(),
+            Layout::Carousel
+        );
+        let result = Layout::from_config_value("carousel").unwrap_err();
+        assert_eq!(
+            result.to_string(),
+            "Expected one of grid, wide-list, round, got carousel"
+        )
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{ConfigContents, ConfigSection, Error, Location};
+use std::{collections::HashMap, env};
+
+/// Section defining variables, which is removed from the contents after interpolation
+pub const VARIABLES_SECTION: &str = "Variables";
+/// Prefix of variables referring to the environment
+const ENV_PREFIX: &str = "env:";
+
+/// A reference to a value, given by the position of its section in
+/// [`ConfigContents::sections`] (`None` for the `[Variables]` section) and its key
+type ValueRef = (Option<usize>, String);
+
+/// Replace all variable references in the values of `contents`
+/// - `${env:NAME}` is replaced by the environment variable `NAME`
+/// - `${Section.key}` is replaced by the value of `key` in `[Section]`
+/// - `${name}` is replaced by `name` in `[Variables]`, the current section or the empty section
+///   (in that order)
+/// - `$$` and `\$` are replaced by a single `$`
+///
+/// Quoted values are taken literally, a reference to them is replaced by their unquoted value.
+/// The `[Variables]` section is removed afterwards
+/// # Errors
+/// Returns an error if a variable is undefined, references are cyclic
+/// or a reference is not terminated by `}`
+pub fn interpolate(contents: &mut ConfigContents) -> Result<(), Error> {
+    let variables = contents.remove_section(VARIABLES_SECTION);
+    let resolved = {
+        let mut resolver = Resolver {
+            contents: &*contents,
+            variables: variables.as_ref(),
+            resolved: HashMap::new(),
+            stack: vec![],
+        };
+        for (ind, section) in contents.sections.iter().enumerate() {
+            for value in &section.values {
+                resolver.resolve(&(Some(ind), value.key.clone()))?;
+            }
+        }
+        resolver.resolved
+    };
+    for ((section, key), value) in resolved {
+        if let Some(config_value) = section
+            .and_then(|ind| contents.sections.get_mut(ind))
+            .and_then(|sec| sec.get_mut(&key))
+        {
+            config_value.value = value;
+        }
+    }
+    Ok(())
+}
+
+struct Resolver<'a> {
+    contents: &'a ConfigContents,
+    variables: Option<&'a ConfigSection>,
+    resolved: HashMap<ValueRef, String>,
+    stack: Vec<ValueRef>,
+}
+
+impl Resolver<'_> {
+    fn section(&self, section: Option<usize>) -> Option<&ConfigSection> {
+        match section {
+            None => self.variables,
+            Some(ind) => self.contents.sections.get(ind),
+        }
+    }
+
+    /// Position of the (first) section called `name`
+    fn find_section(&self, name: &str) -> Option<Option<usize>> {
+        if name == VARIABLES_SECTION {
+            Some(None)
+        } else {
+            self.contents
+                .sections
+                .iter()
+                .position(|sec| sec.name == name)
+                .map(Some)
+        }
+    }
+
+    fn format_ref(&self, (section, key): &ValueRef) -> String {
+        let name = self
+            .section(*section)
+            .map_or(VARIABLES_SECTION, |sec| sec.name.as_str());
+        if name.is_empty() {
+            key.clone()
+        } else {
+            format!("{name}.{key}")
+        }
+    }
+
+    fn location(&self, (section, key): &ValueRef) -> Location {
+        let span = self
+            .section(*section)
+            .and_then(|sec| sec.get(key))
+            .map(|value| value.value_span)
+            .unwrap_or_default();
+        Location::new(&self.contents.path, &self.contents.source, span)
+    }
+
+    /// Find the value a variable `name` used in `current_section` refers to
+    fn lookup(&self, name: &str, current_section: Option<usize>) -> Option<ValueRef> {
+        let candidates = match name.rsplit_once('.') {
+            Some((section, key)) => vec![(self.find_section(section)?, key)],
+            None => [Some(None), Some(current_section), self.find_section("")]
+                .into_iter()
+                .flatten()
+                .map(|section| (section, name))
+                .collect(),
+        };
+        candidates
+            .into_iter()
+            .find(|(section, key)| {
+                self.section(*section)
+                    .is_some_and(|sec| sec.contains_key(key))
+            })
+            .map(|(section, key)| (section, key.to_owned()))
+    }
+
+    fn resolve(&mut self, value_ref: &ValueRef) -> Result<String, Error> {
+        if let Some(value) = self.resolved.get(value_ref) {
+            return Ok(value.clone());
+        }
+        if let Some(start) = self.stack.iter().position(|prev| prev == value_ref) {
+            let cycle = self.stack[start..]
+                .iter()
+                .chain([value_ref])
+                .map(|value_ref| self.format_ref(value_ref))
+                .collect();
+            return Err(Error::variable_cycle(
+                self.location(&self.stack[start]),
+                cycle,
+            ));
+        }
+        let Some(raw) = self
+            .section(value_ref.0)
+            .and_then(|sec| sec.get(&value_ref.1))
+        else {
+            return Ok(String::new());
+        };
+        let (raw, literal) = (raw.value.clone(), raw.literal);
+        let value = if literal {
+            raw
+        } else {
+            self.stack.push(value_ref.clone());
+            let value = self.expand(&raw, value_ref)?;
+            self.stack.pop();
+            value
+        };
+        self.resolved.insert(value_ref.clone(), value.clone());
+        Ok(value)
+    }
+
+    fn expand(&mut self, raw: &str, value_ref: &ValueRef) -> Result<String, Error> {
+        let mut expanded = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(ind) = rest.find(['$', '\\']) {
+            expanded.push_str(&rest[..ind]);
+            let escape = rest[ind..].starts_with('\\');
+            rest = &rest[ind + 1..];
+            if escape {
+                if let Some(after) = rest.strip_prefix('$') {
+                    expanded.push('$');
+                    rest = after;
+                } else {
+                    expanded.push('\\');
+                }
+                continue;
+            }
+            if let Some(after) = rest.strip_prefix('$') {
+                expanded.push('$');
+                rest = after;
+                continue;
+            }
+            let Some(after) = rest.strip_prefix('{') else {
+                expanded.push('$');
+                continue;
+            };
+            let (name, after) = after.split_once('}').ok_or_else(|| {
+                Error::format(
+                    self.location(value_ref),
+                    "Variable reference is missing closing }",
+                )
+            })?;
+            let name = name.trim();
+            let value = if let Some(var) = name.strip_prefix(ENV_PREFIX) {
+                env::var(var).ok()
+            } else {
+                match self.lookup(name, value_ref.0) {
+                    Some(target) => Some(self.resolve(&target)?),
+                    None => None,
+                }
+            };
+            let value =
+                value.ok_or_else(|| Error::undefined_variable(self.location(value_ref), name))?;
+            expanded.push_str(&value);
+            rest = after;
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+}
+
+#[cfg(test)]
+mod interpolate_tests {
+    use crate::{Duplicates, Error, contents_from_string};
+    use std::path::PathBuf;
+
+    fn value(input: &str, section: &str, key: &str) -> String {
+        let contents =
+            contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error).unwrap();
+        contents
+            .section(section)
+            .unwrap()
+            .get(key)
+            .unwrap()
+            .value
+            .clone()
+    }
+
+    #[test]
+    fn variables_section() {
+        let input = "[Variables]\naccent = #b166cc\n[Entries]\nbackground-active = ${accent}";
+        let result = value(input, "Entries", "background-active");
+        assert_eq!(result, "#b166cc")
+    }
+
+    #[test]
+    fn other_section() {
+        let input = "text-size = 26\n[Entries]\ntext-size = ${.text-size}\nwidth = ${text-size}0";
+        assert_eq!(value(input, "Entries", "text-size"), "26");
+        assert_eq!(value(input, "Entries", "width"), "260");
+    }
+
+    #[test]
+    fn env_var() {
+        let input = "path = ${env:PATH}";
+        assert_eq!(value(input, "", "path"), std::env::var("PATH").unwrap())
+    }
+
+    #[test]
+    fn escape() {
+        let input = "price = $$5";
+        assert_eq!(value(input, "", "price"), "$5")
+    }
+
+    #[test]
+    fn quoted_literal() {
+        let input = "x = 1\nquoted = \"${x}\"\nescaped = \\${x}\nref = ${quoted}\npath = a\\b";
+        assert_eq!(value(input, "", "quoted"), "${x}");
+        assert_eq!(value(input, "", "escaped"), "${x}");
+        assert_eq!(value(input, "", "ref"), "${x}");
+        assert_eq!(value(input, "", "path"), "a\\b")
+    }
+
+    #[test]
+    fn variables_removed() {
+        let input = "[Variables]\na = 1";
+        let contents =
+            contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error).unwrap();
+        assert!(contents.sections.is_empty())
+    }
+
+    #[test]
+    fn undefined() {
+        let input = "a = 1\nb = ${c}";
+        let result = contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error);
+        assert!(matches!(
+            result,
+            Err(Error::UndefinedVariable { location, .. }) if location.span.line == 2
+        ))
+    }
+
+    #[test]
+    fn cycle() {
+        let input = "a = ${b}\nb = ${a}";
+        let result = contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error);
+        assert!(matches!(result, Err(Error::VariableCycle { .. })))
+    }
+
+    #[test]
+    fn repeated_current_section() {
+        let input = "[[Entry]]\nname = a\ntitle = ${name}\n[[Entry]]\nname = b\ntitle = ${name}";
+        let contents =
+            contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error).unwrap();
+        let titles: Vec<&str> = contents
+            .sections
+            .iter()
+            .map(|sec| sec.get("title").unwrap().value.as_str())
+            .collect();
+        assert_eq!(titles, vec!["a", "b"])
+    }
+}
@@ -1,12 +1,31 @@
-use std::{collections::HashMap, fs::read_to_string, path::PathBuf};
+use std::{fs::read_to_string, path::PathBuf};
+
+// allows `#[derive(ConfigBuilder)]` to refer to `::parser` inside this crate
+extern crate self as parser;
 
 mod config_builder;
 mod contents;
+mod diagnostics;
+mod document;
 mod errors;
+mod from_value;
+mod interpolate;
+mod overrides;
+mod schema;
+mod values;
 use config_builder::from_contents;
-pub use config_builder::{ConfigBuilder, Key, Section};
-use contents::{ConfigContents, ConfigSection};
-pub use errors::Error;
+pub use config_builder::{ANY_SECTION, ConfigBuilder, Configurable, Duplicates, Key, Section};
+use contents::{ConfigContents, ConfigSection, ConfigValue};
+pub use diagnostics::{Location, Span, Unexpected};
+pub use document::{ConfigDocument, ToConfig};
+pub use errors::{BoxedError, Error};
+pub use from_value::{FromConfigValue, ToConfigValue};
+pub use interpolate::VARIABLES_SECTION;
+use interpolate::interpolate;
+pub use overrides::Override;
+pub use parser_derive::{ConfigBuilder, FromConfigValue, ToConfigValue};
+pub use schema::{KeySchema, Schema, SectionSchema};
+use values::{read_value, remove_comment};
 
 /// Parse a given file with the given builder type
 /// # Errors
@@ -15,53 +34,228 @@ pub fn parse_file<Builder>(path: PathBuf) -> Result<Builder::Output, Builder::Er
 where
     Builder: ConfigBuilder,
 {
-    let contents = contents_from_file(path)?;
-    from_contents::<Builder>(contents)
+    parse_file_with::<Builder>(path, &[])
+}
+
+/// Parse a given file with the given builder type
+/// and apply the given overrides after all values in the file
+/// # Errors
+/// Returns an error if the file cannot be read, if the contents cannot be parsed
+/// or if an override cannot be applied
+pub fn parse_file_with<Builder>(
+    path: PathBuf,
+    overrides: &[Override],
+) -> Result<Builder::Output, Builder::Error>
+where
+    Builder: ConfigBuilder,
+{
+    let contents = contents_from_file(path, Builder::duplicates())?;
+    from_contents::<Builder>(contents, overrides)
 }
 
 /// Parse file to generic [`ConfigContents`]
 /// # Errors
 /// Returns an error if the file could not be read or if the contents could not be parsed
-fn contents_from_file(path: PathBuf) -> Result<ConfigContents, Error> {
+fn contents_from_file(path: PathBuf, duplicates: Duplicates) -> Result<ConfigContents, Error> {
     let path_contents = read_to_string(&path).map_err(|err| Error::read_file(&err, &path))?;
-    contents_from_string(&path_contents, path)
+    contents_from_string(&path_contents, path, duplicates)
 }
 
 /// Parse string to generic [`ConfigContents`]
 /// # Errors
 /// Returns an error if the contents could not be parsed
-fn contents_from_string(input: &str, path: PathBuf) -> Result<ConfigContents, Error> {
-    let mut sections = HashMap::new();
-    let mut values = HashMap::new();
-    let mut current_section = String::new();
-    for (num, line) in input.lines().enumerate() {
-        let line = remove_comment(line);
+/// or if keys or sections are repeated and `duplicates` is [`Duplicates::Error`]
+fn contents_from_string(
+    input: &str,
+    path: PathBuf,
+    duplicates: Duplicates,
+) -> Result<ConfigContents, Error> {
+    let location = |span| Location::new(&path, input, span);
+    let mut sections: Vec<ConfigSection> = vec![];
+    // name, location and whether the section is repeated for each header
+    let mut headers: Vec<(String, Span, bool)> = vec![];
+    let mut current = ConfigSection {
+        name: String::new(),
+        index: None,
+        values: vec![],
+        span: Span::default(),
+    };
+    let mut input_lines = input.lines().enumerate();
+    while let Some((num, raw_line)) = input_lines.next() {
+        let line_nr = num + 1;
+        let line = remove_comment(raw_line).trim();
         if line.is_empty() {
             continue;
         }
         if line.starts_with('[') && line.ends_with(']') {
-            if !values.is_empty() {
-                sections.insert(current_section, ConfigSection { values });
+            let repeated = line.starts_with("[[") && line.ends_with("]]");
+            let name = line.replace(['[', ']'], "");
+            let span = Span::of(line_nr, raw_line, line);
+            let same_name: Vec<&(String, Span, bool)> = headers
+                .iter()
+                .filter(|(header, _, _)| *header == name)
+                .collect();
+            if same_name.iter().any(|(_, _, rep)| *rep != repeated) {
+                return Err(Error::format(
+                    location(span),
+                    &format!("Section {name} is used both as [{name}] and [[{name}]]"),
+                ));
+            }
+            let index = repeated.then_some(same_name.len());
+            let first = same_name.first().map(|(_, span, _)| *span);
+            headers.push((name.clone(), span, repeated));
+            let previous = std::mem::replace(
+                &mut current,
+                ConfigSection {
+                    name,
+                    index,
+                    values: vec![],
+                    span,
+                },
+            );
+            // elements of repeated sections are kept even without values to keep their index
+            if !previous.values.is_empty() || previous.index.is_some() {
+                sections.push(previous);
+            }
+            if let Some(first_span) = first
+                && !repeated
+            {
+                if duplicates == Duplicates::Error {
+                    return Err(Error::duplicate_section(
+                        &current.name,
+                        location(first_span),
+                        location(span),
+                    ));
+                }
+                sections.retain(|sec| sec.name != current.name);
             }
-            values = HashMap::new();
-            current_section = line.replace(['[', ']'], "");
             continue;
         }
-        let (key, val) = line
-            .split_once('=')
-            .ok_or_else(|| Error::format(&path, num, "Entries need to be in key=value format"))?;
-        values.insert(key.trim().to_owned(), val.trim().to_owned());
+        let (key, val) = line.split_once('=').ok_or_else(|| {
+            Error::format(
+                location(Span::of(line_nr, raw_line, line)),
+                "Entries need to be in key=value format",
+            )
+        })?;
+        let key = key.trim();
+        let val = val.trim();
+        let key_span = Span::of(line_nr, raw_line, key);
+        let value_span = Span::of(line_nr, raw_line, val);
+        let literal = val.starts_with('"');
+        let value = read_value(val, &mut input_lines)
+            .map_err(|reason| Error::format(location(value_span), &reason))?;
+        if let Some(first) = current.remove(key)
+            && duplicates == Duplicates::Error
+        {
+            return Err(Error::duplicate_key(
+                &current.name,
+                key,
+                location(first.key_span),
+                location(key_span),
+            ));
+        }
+        current.values.push(ConfigValue {
+            key: key.to_owned(),
+            value,
+            key_span,
+            value_span,
+            literal,
+        });
     }
-    if !values.is_empty() {
-        sections.insert(current_section, ConfigSection { values });
+    if !current.values.is_empty() || current.index.is_some() {
+        sections.push(current);
     }
-    Ok(ConfigContents { sections, path })
+    let mut contents = ConfigContents {
+        sections,
+        path,
+        source: input.to_owned(),
+    };
+    interpolate(&mut contents)?;
+    Ok(contents)
 }
 
-/// Remove comment from a given line
-fn remove_comment(line: &str) -> &str {
-    match line.split_once("//") {
-        Some((start, _)) => start,
-        None => line,
+#[cfg(test)]
+mod contents_tests {
+    use super::{Duplicates, Error, contents_from_string};
+    use std::path::PathBuf;
+
+    #[test]
+    fn duplicate_key() {
+        let input = "title = a\nlaunch = b\ntitle = c";
+        let result = contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error);
+        assert!(matches!(
+            result,
+            Err(Error::DuplicateKey { first, second, .. })
+                if first.span.line == 1 && second.span.line == 3
+        ))
+    }
+
+    #[test]
+    fn duplicate_section() {
+        let input = "[Entries]\na = 1\n[Other]\nb = 2\n[Entries]\nc = 3";
+        let result = contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error);
+        assert!(matches!(
+            result,
+            Err(Error::DuplicateSection { first, second, .. })
+                if first.span.line == 1 && second.span.line == 5
+        ))
+    }
+
+    #[test]
+    fn duplicate_key_last_wins() {
+        let input = "title = a\ntitle = c";
+        let result =
+            contents_from_string(input, PathBuf::from("test.conf"), Duplicates::LastWins).unwrap();
+        assert_eq!(result.sections[0].values[0].value, "c")
+    }
+
+    #[test]
+    fn duplicate_section_last_wins() {
+        let input = "[Entries]\na = 1\n[Entries]\nb = 2";
+        let result =
+            contents_from_string(input, PathBuf::from("test.conf"), Duplicates::LastWins).unwrap();
+        let section = result.section("Entries").unwrap();
+        assert!(!section.contains_key("a"));
+        assert_eq!(section.get("b").unwrap().value, "2")
+    }
+
+    #[test]
+    fn order_of_appearance() {
+        let input = "b = 1\na = 2\n[Zeta]\nc = 3\n[Alpha]\nd = 4";
+        let result =
+            contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error).unwrap();
+        let sections: Vec<&str> = result
+            .sections
+            .iter()
+            .map(|sec| sec.name.as_str())
+            .collect();
+        assert_eq!(sections, vec!["", "Zeta", "Alpha"]);
+        let keys: Vec<&str> = result.sections[0]
+            .values
+            .iter()
+            .map(|value| value.key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["b", "a"])
+    }
+
+    #[test]
+    fn repeated_sections() {
+        let input = "[[Entry]]\ntitle = a\n[[Entry]]\n[Other]\nb = 1\n[[Entry]]\ntitle = c";
+        let result =
+            contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error).unwrap();
+        let entries: Vec<Option<usize>> = result
+            .sections
+            .iter()
+            .filter(|sec| sec.name == "Entry")
+            .map(|sec| sec.index)
+            .collect();
+        assert_eq!(entries, vec![Some(0), Some(1), Some(2)])
+    }
+
+    #[test]
+    fn repeated_and_single_section() {
+        let input = "[Entry]\ntitle = a\n[[Entry]]\ntitle = b";
+        let result = contents_from_string(input, PathBuf::from("test.conf"), Duplicates::Error);
+        assert!(matches!(result, Err(Error::InvalidFormat { .. })))
     }
 }
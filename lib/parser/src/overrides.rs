@@ -0,0 +1,110 @@
+use crate::Error;
+use std::env::vars;
+
+/// A value set outside of a config file, e.g. from the environment or the command line
+/// Overrides are applied after all values in the file have been parsed,
+/// before required keys are checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Override {
+    /// Section of the value, matched case-insensitively
+    pub section: String,
+    /// Key of the value, matched case-insensitively
+    pub key: String,
+    /// The value, parsed the same way as values in the file but never interpolated
+    pub value: String,
+    /// Where the override came from, used in error messages
+    pub origin: String,
+    /// Whether an unknown section or key is an error, otherwise it is reported and skipped
+    pub strict: bool,
+}
+
+impl Override {
+    /// Create an override from an environment variable
+    /// `{prefix}{SECTION}__{KEY}` sets `key` in `[Section]`,
+    /// `{prefix}{KEY}` sets `key` in the empty section,
+    /// underscores in the key are replaced by `-`.
+    /// Variables that match no section or key are skipped, see [`Self::strict`]
+    /// Returns `None` if `name` does not start with `prefix`
+    #[must_use]
+    pub fn from_env_var(prefix: &str, name: &str, value: &str) -> Option<Self> {
+        let name_rest = name.strip_prefix(prefix)?;
+        let (section, key) = name_rest.split_once("__").unwrap_or(("", name_rest));
+        Some(Self {
+            section: section.to_owned(),
+            key: key.to_lowercase().replace('_', "-"),
+            value: value.to_owned(),
+            origin: name.to_owned(),
+            strict: false,
+        })
+    }
+
+    /// Collect all overrides from environment variables starting with `prefix`
+    /// see [`Self::from_env_var`]
+    #[must_use]
+    pub fn from_env(prefix: &str) -> Vec<Self> {
+        vars()
+            .filter_map(|(name, value)| Self::from_env_var(prefix, &name, &value))
+            .collect()
+    }
+
+    /// Create an override from a command line argument
+    /// `Section.key=value` sets `key` in `[Section]`,
+    /// `key=value` sets `key` in the empty section
+    /// # Errors
+    /// Returns an error if the argument does not contain `=`
+    pub fn from_arg(arg: &str) -> Result<Self, Error> {
+        let (name, value) = arg.split_once('=').ok_or_else(|| {
+            Error::invalid_override(arg, "Overrides need to be in key=value format")
+        })?;
+        let (section, key) = name.rsplit_once('.').unwrap_or(("", name));
+        Ok(Self {
+            section: section.trim().to_owned(),
+            key: key.trim().to_owned(),
+            value: value.trim().to_owned(),
+            origin: arg.to_owned(),
+            strict: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod override_tests {
+    use super::Override;
+
+    #[test]
+    fn env_var_section() {
+        let result = Override::from_env_var("TVMENU_", "TVMENU_ENTRIES__TEXT_SIZE", "40").unwrap();
+        assert_eq!(result.section, "ENTRIES");
+        assert_eq!(result.key, "text-size");
+        assert_eq!(result.value, "40");
+    }
+
+    #[test]
+    fn env_var_empty_section() {
+        let result = Override::from_env_var("TVMENU_", "TVMENU_ROW_GAP", "5").unwrap();
+        assert_eq!(result.section, "");
+        assert_eq!(result.key, "row-gap");
+        assert!(!result.strict)
+    }
+
+    #[test]
+    fn env_var_other_prefix() {
+        let result = Override::from_env_var("TVMENU_", "HOME", "/root");
+        assert!(result.is_none())
+    }
+
+    #[test]
+    fn arg_section() {
+        let result = Override::from_arg("Entries.text-size=40").unwrap();
+        assert_eq!(result.section, "Entries");
+        assert_eq!(result.key, "text-size");
+        assert_eq!(result.value, "40");
+        assert!(result.strict)
+    }
+
+    #[test]
+    fn arg_no_value() {
+        let result = Override::from_arg("Entries.text-size");
+        assert!(result.is_err())
+    }
+}
@@ -0,0 +1,196 @@
+use std::fmt::Write;
+
+/// Description of the sections and keys used by a [`crate::ConfigBuilder`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    pub sections: Vec<SectionSchema>,
+}
+
+/// A section of a [`Schema`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionSchema {
+    /// Name of the section, empty for the section before any header
+    pub name: String,
+    pub optional: bool,
+    /// Whether the section is written as `[[name]]` and can be given any number of times
+    pub repeated: bool,
+    pub description: String,
+    pub keys: Vec<KeySchema>,
+}
+
+/// A key of a [`SectionSchema`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeySchema {
+    pub name: String,
+    /// Description of the expected value, see [`crate::FromConfigValue::expected`]
+    pub value_type: String,
+    /// The value used when the key is missing, as written in a config file
+    pub default: Option<String>,
+    pub optional: bool,
+    pub description: String,
+}
+
+impl Schema {
+    /// Render the schema as markdown, with a table of keys for each section
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        for section in &self.sections {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            if section.name.is_empty() {
+                out.push_str("### Top level\n\n");
+            } else if section.repeated {
+                let _ = writeln!(out, "### `[[{}]]`\n", section.name);
+            } else {
+                let _ = writeln!(out, "### `[{}]`\n", section.name);
+            }
+            if !section.description.is_empty() {
+                let _ = writeln!(out, "{}\n", section.description);
+            }
+            out.push_str("| Key | Type | Default | Description |\n");
+            out.push_str("| --- | --- | --- | --- |\n");
+            for key in &section.keys {
+                let default = match &key.default {
+                    Some(default) if default.is_empty() => "empty".to_owned(),
+                    Some(default) => format!("`{default}`"),
+                    None if key.optional => "not set".to_owned(),
+                    None => "required".to_owned(),
+                };
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} |",
+                    key.name,
+                    table_cell(&key.value_type),
+                    table_cell(&default),
+                    table_cell(&key.description)
+                );
+            }
+        }
+        out
+    }
+
+    /// Render the schema as JSON
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let sections: Vec<String> = self
+            .sections
+            .iter()
+            .map(|section| {
+                let keys: Vec<String> = section
+                    .keys
+                    .iter()
+                    .map(|key| {
+                        format!(
+                            "{{\"name\": {}, \"type\": {}, \"default\": {}, \"optional\": {}, \"description\": {}}}",
+                            json_string(&key.name),
+                            json_string(&key.value_type),
+                            key.default.as_deref().map_or_else(|| "null".to_owned(), json_string),
+                            key.optional,
+                            json_string(&key.description)
+                        )
+                    })
+                    .collect();
+                format!(
+                    "{{\"name\": {}, \"optional\": {}, \"repeated\": {}, \"description\": {}, \"keys\": [{}]}}",
+                    json_string(&section.name),
+                    section.optional,
+                    section.repeated,
+                    json_string(&section.description),
+                    keys.join(", ")
+                )
+            })
+            .collect();
+        format!("{{\"sections\": [{}]}}", sections.join(", "))
+    }
+}
+
+/// Escape a value for a markdown table, line breaks are joined with spaces
+fn table_cell(value: &str) -> String {
+    value
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+/// Quote and escape a JSON string
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{:04x}", u32::from(ch));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod schema_tests {
+    use super::{KeySchema, Schema, SectionSchema};
+
+    fn schema() -> Schema {
+        Schema {
+            sections: vec![SectionSchema {
+                name: "Entries".to_owned(),
+                optional: true,
+                repeated: false,
+                description: "How entries are shown".to_owned(),
+                keys: vec![
+                    KeySchema {
+                        name: "width".to_owned(),
+                        value_type: "number (f32)".to_owned(),
+                        default: Some("100".to_owned()),
+                        optional: true,
+                        description: "Width of an entry\nin pixels".to_owned(),
+                    },
+                    KeySchema {
+                        name: "title".to_owned(),
+                        value_type: "text".to_owned(),
+                        default: None,
+                        optional: false,
+                        description: "A \"title\" | name".to_owned(),
+                    },
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn markdown() {
+        let result = schema().to_markdown();
+        let expected = "### `[Entries]`
+
+How entries are shown
+
+| Key | Type | Default | Description |
+| --- | --- | --- | --- |
+| `width` | number (f32) | `100` | Width of an entry in pixels |
+| `title` | text | required | A \"title\" \\| name |
+";
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn json() {
+        let result = schema().to_json();
+        assert!(result.starts_with(
+            r#"{"sections": [{"name": "Entries", "optional": true, "repeated": false"#
+        ));
+        assert!(result.contains(r#""default": null"#));
+        assert!(result.contains(r#""description": "Width of an entry\nin pixels""#));
+        assert!(result.contains(r#""description": "A \"title\" | name""#))
+    }
+}
@@ -0,0 +1,227 @@
+/// Delimiter of multi-line values
+const TRIPLE_QUOTE: &str = "\"\"\"";
+
+/// Remove a comment from a given line
+/// `//` starts a comment at the start of the line or after whitespace,
+/// but never inside a quoted value, so `https://example.com` is kept intact
+pub fn remove_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut prev = ' ';
+    for (ind, ch) in line.char_indices() {
+        if in_quotes {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_quotes = false,
+                _ => (),
+            }
+        } else if ch == '"' {
+            in_quotes = true;
+        } else if prev.is_whitespace() && line[ind..].starts_with("//") {
+            return &line[..ind];
+        }
+        prev = ch;
+    }
+    line
+}
+
+/// Read the value of a `key = value` pair
+/// `first` is the (trimmed) part of the line after `=`,
+/// `lines` are the following lines of the input, used for multi-line values
+/// - `"..."` is a quoted value, which can contain escape sequences
+/// - `"""..."""` is a multi-line value, taken verbatim
+/// - otherwise the value is unquoted and a trailing `\\` continues it on the next line
+///
+/// # Errors
+/// Returns the reason if a quote is not terminated, a quoted value is followed by other text
+/// or an escape sequence is invalid
+pub fn read_value<'a, Lines>(first: &str, lines: &mut Lines) -> Result<String, String>
+where
+    Lines: Iterator<Item = (usize, &'a str)>,
+{
+    if let Some(rest) = first.strip_prefix(TRIPLE_QUOTE) {
+        read_multiline(rest, lines)
+    } else if let Some(rest) = first.strip_prefix('"') {
+        read_quoted(rest)
+    } else {
+        let mut value = first.to_owned();
+        while let Some(continued) = value.strip_suffix('\\') {
+            let mut next = continued.to_owned();
+            let (_, line) = lines
+                .next()
+                .ok_or_else(|| "Line continuation at the end of the file".to_owned())?;
+            next.push_str(remove_comment(line).trim());
+            value = next;
+        }
+        Ok(value.trim().to_owned())
+    }
+}
+
+/// Format a value so that [`read_value`] reads it back unchanged
+/// values are quoted if they have surrounding whitespace, line breaks, quotes,
+/// a trailing `\\`, a `$` that would be interpolated or would start a comment
+pub fn format_value(value: &str) -> String {
+    let needs_quotes = value.trim() != value
+        || value.starts_with('"')
+        || value.contains('$')
+        || value.ends_with('\\')
+        || value.chars().any(char::is_control)
+        || remove_comment(value) != value;
+    if !needs_quotes {
+        return value.to_owned();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for ch in value.chars() {
+        match ch {
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\0' => quoted.push_str("\\0"),
+            '\\' | '"' => {
+                quoted.push('\\');
+                quoted.push(ch);
+            }
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Read a quoted value, `rest` is the input after the opening `"`
+fn read_quoted(rest: &str) -> Result<String, String> {
+    let mut value = String::with_capacity(rest.len());
+    let mut chars = rest.char_indices();
+    while let Some((ind, ch)) = chars.next() {
+        match ch {
+            '"' => {
+                if !rest[ind + 1..].trim().is_empty() {
+                    return Err("Unexpected characters after quoted value".to_owned());
+                }
+                return Ok(value);
+            }
+            '\\' => {
+                let escaped = match chars.next().map(|(_, esc)| esc) {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('0') => '\0',
+                    Some(esc @ ('\\' | '"')) => esc,
+                    Some(esc) => return Err(format!("Invalid escape sequence \\{esc}")),
+                    None => break,
+                };
+                value.push(escaped);
+            }
+            _ => value.push(ch),
+        }
+    }
+    Err("Missing closing quote".to_owned())
+}
+
+/// Read a multi-line value, `rest` is the input after the opening `"""`
+/// a line break directly after the opening `"""` is not part of the value
+fn read_multiline<'a, Lines>(rest: &str, lines: &mut Lines) -> Result<String, String>
+where
+    Lines: Iterator<Item = (usize, &'a str)>,
+{
+    let mut value = String::new();
+    let mut current = rest;
+    let mut first_line = true;
+    loop {
+        if let Some((end, after)) = current.split_once(TRIPLE_QUOTE) {
+            if !remove_comment(after).trim().is_empty() {
+                return Err("Unexpected characters after multi-line value".to_owned());
+            }
+            value.push_str(end);
+            return Ok(value);
+        }
+        if !(first_line && current.trim().is_empty()) {
+            value.push_str(current);
+            value.push('\n');
+        }
+        first_line = false;
+        current = lines
+            .next()
+            .ok_or_else(|| "Missing closing \"\"\"".to_owned())?
+            .1;
+    }
+}
+
+#[cfg(test)]
+mod value_tests {
+    use super::{format_value, read_value, remove_comment};
+
+    fn read(input: &str) -> String {
+        let mut lines = input.lines().enumerate();
+        let first = lines.next().unwrap().1;
+        read_value(remove_comment(first).trim(), &mut lines).unwrap()
+    }
+
+    #[test]
+    fn comment_after_whitespace() {
+        assert_eq!(remove_comment("a = b // comment"), "a = b ")
+    }
+
+    #[test]
+    fn comment_in_url() {
+        let result = read("firefox https://example.com");
+        assert_eq!(result, "firefox https://example.com")
+    }
+
+    #[test]
+    fn comment_in_quotes() {
+        let result = read("\"a // b\" // comment");
+        assert_eq!(result, "a // b")
+    }
+
+    #[test]
+    fn quoted_escapes() {
+        let result = read(r#""say \"hi\"\n\ttab\\""#);
+        assert_eq!(result, "say \"hi\"\n\ttab\\")
+    }
+
+    #[test]
+    fn quoted_whitespace() {
+        let result = read("\"  padded  \"");
+        assert_eq!(result, "  padded  ")
+    }
+
+    #[test]
+    fn continuation() {
+        let result = read("mpv \\\n  --fs \\\n  video.mkv");
+        assert_eq!(result, "mpv --fs video.mkv")
+    }
+
+    #[test]
+    fn multiline() {
+        let result = read("\"\"\"\nfirst\nsecond\"\"\"");
+        assert_eq!(result, "first\nsecond")
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        let mut lines = std::iter::empty();
+        let result = read_value("\"open", &mut lines);
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn format_round_trip() {
+        for value in [
+            "plain",
+            " padded",
+            "a // b",
+            "say \"hi\"\n",
+            "end\\",
+            "$5",
+            "",
+        ] {
+            let formatted = format_value(value);
+            let mut lines = std::iter::empty();
+            assert_eq!(read_value(&formatted, &mut lines).unwrap(), value)
+        }
+        assert_eq!(format_value("https://example.com"), "https://example.com")
+    }
+}
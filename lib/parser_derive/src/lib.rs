@@ -0,0 +1,921 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Expr, ExprLit, Fields, GenericArgument, Ident, Lit, LitStr, Meta,
+    Path, PathArguments, Type, parse_macro_input, spanned::Spanned,
+};
+
+/// Derive a [`ConfigBuilder`](../parser/trait.ConfigBuilder.html) for a config struct
+///
+/// This generates a builder struct (named `{Struct}Builder` unless set with `builder = Name`)
+/// implementing `ConfigBuilder` with `Output = Struct` and implements `Configurable` for the struct.
+///
+/// Struct attributes (`#[config(...)]`)
+/// - `error = Type` (required) - the error type of the builder, needs to implement
+///   `From<parser::Error>`
+/// - `builder = Name` - name of the generated builder
+/// - `duplicates = "last-wins"` - repeated keys and sections replace earlier ones
+///   instead of being reported, see `Duplicates`
+///
+/// Field attributes (`#[config(...)]`)
+/// - `key = "name"` - key in the config file, defaults to the field name with `_` replaced by `-`
+/// - `section = "Name"` - section of the key, defaults to the empty section
+/// - `default = expr` - value used when the key is missing, makes the key optional
+/// - `optional` - the field is an `Option` and `None` when the key is missing
+/// - `parser = path` - function `fn(&str) -> Result<T, E>` with `E: Into<Error>`
+///   used to parse the value, defaults to `FromConfigValue`
+/// - `nested` - the field type also derives `ConfigBuilder`
+///   and its keys are read from `section`
+/// - `repeated` - the field is a `Vec<T>` where `T` derives `ConfigBuilder`,
+///   with one element for each `[[section]]`
+/// - `skip` - the field is not read from the config and set to [`Default::default`]
+///
+/// Fields without `default`, `optional`, `nested`, `repeated` or `skip` are required.
+#[proc_macro_derive(ConfigBuilder, attributes(config))]
+pub fn derive_config_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive [`FromConfigValue`](../parser/trait.FromConfigValue.html) for an enum without fields
+///
+/// Each variant is parsed from its name in kebab-case (`WideList` is `wide-list`),
+/// ignoring case. Use `#[config(name = "...")]` on a variant to choose another name.
+#[proc_macro_derive(FromConfigValue, attributes(config))]
+pub fn derive_from_config_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_enum(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive [`ToConfigValue`](../parser/trait.ToConfigValue.html) for an enum without fields
+///
+/// Variants are written with the same names as parsed by `#[derive(FromConfigValue)]`
+#[proc_macro_derive(ToConfigValue, attributes(config))]
+pub fn derive_to_config_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_value(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct StructAttrs {
+    error: Path,
+    builder: Ident,
+    /// variant of `Duplicates`, `None` for the default
+    duplicates: Option<Ident>,
+}
+
+/// A field read from a single key
+struct ValueField {
+    section: String,
+    key: String,
+    default: Option<Expr>,
+    optional: bool,
+    parser: Option<Path>,
+    /// type of the parsed value, that is `T` for `optional` fields of type `Option<T>`
+    value_ty: Type,
+}
+
+enum FieldKind {
+    Value(Box<ValueField>),
+    Nested {
+        section: String,
+    },
+    /// one element for each `[[section]]`
+    Repeated {
+        section: String,
+        element: Box<Type>,
+    },
+    Skip,
+}
+
+struct ConfigField {
+    ident: Ident,
+    /// doc comment of the field
+    doc: String,
+    ty: Type,
+    kind: FieldKind,
+}
+
+impl ValueField {
+    /// Whether the key can be left out
+    const fn is_optional(&self) -> bool {
+        self.default.is_some() || self.optional
+    }
+}
+
+impl ConfigField {
+    /// The section the field is read from
+    fn section(&self) -> Option<&str> {
+        match &self.kind {
+            FieldKind::Value(value) => Some(&value.section),
+            FieldKind::Nested { section } | FieldKind::Repeated { section, .. } => Some(section),
+            FieldKind::Skip => None,
+        }
+    }
+}
+
+fn parse_struct_attrs(input: &DeriveInput) -> syn::Result<StructAttrs> {
+    let mut error = None;
+    let mut builder = format_ident!("{}Builder", input.ident);
+    let mut duplicates = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("config"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("error") {
+                error = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("builder") {
+                builder = meta.value()?.parse()?;
+            } else if meta.path.is_ident("duplicates") {
+                let value = meta.value()?.parse::<LitStr>()?;
+                let variant = match value.value().as_str() {
+                    "error" => "Error",
+                    "last-wins" => "LastWins",
+                    _ => {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "expected \"error\" or \"last-wins\"",
+                        ));
+                    }
+                };
+                duplicates = Some(format_ident!("{variant}"));
+            } else {
+                return Err(meta.error("unknown config attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    let error = error.ok_or_else(|| {
+        syn::Error::new(
+            input.ident.span(),
+            "missing #[config(error = Type)] attribute",
+        )
+    })?;
+    Ok(StructAttrs {
+        error,
+        builder,
+        duplicates,
+    })
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<ConfigField> {
+    let ident = field
+        .ident
+        .clone()
+        .ok_or_else(|| syn::Error::new(field.span(), "tuple structs are not supported"))?;
+    let mut key = ident.to_string().replace('_', "-");
+    let mut section = String::new();
+    let mut default = None;
+    let mut optional = false;
+    let mut parser = None;
+    let mut nested = false;
+    let mut repeated = false;
+    let mut skip = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("config"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") {
+                key = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("section") {
+                section = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("parser") {
+                parser = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("optional") {
+                optional = true;
+            } else if meta.path.is_ident("nested") {
+                nested = true;
+            } else if meta.path.is_ident("repeated") {
+                repeated = true;
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+            } else {
+                return Err(meta.error("unknown config attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    let kind = if skip {
+        FieldKind::Skip
+    } else if nested {
+        FieldKind::Nested { section }
+    } else if repeated {
+        let element = generic_inner(&field.ty, "Vec").ok_or_else(|| {
+            syn::Error::new(field.ty.span(), "repeated fields need to have type Vec<T>")
+        })?;
+        FieldKind::Repeated {
+            section,
+            element: Box::new(element),
+        }
+    } else {
+        let value_ty = if optional {
+            generic_inner(&field.ty, "Option").ok_or_else(|| {
+                syn::Error::new(
+                    field.ty.span(),
+                    "optional fields need to have type Option<T>",
+                )
+            })?
+        } else {
+            field.ty.clone()
+        };
+        FieldKind::Value(Box::new(ValueField {
+            section,
+            key,
+            default,
+            optional,
+            parser,
+            value_ty,
+        }))
+    };
+    Ok(ConfigField {
+        ident,
+        doc: doc_comment(&field.attrs),
+        ty: field.ty.clone(),
+        kind,
+    })
+}
+
+/// Lines of the `///` comments in `attrs`
+fn doc_comment(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(doc) if doc.path.is_ident("doc") => match &doc.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(line),
+                    ..
+                }) => Some(line.value().trim().to_owned()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Write `doc` as a comment, if it is not empty
+fn write_comment(doc: &str) -> TokenStream2 {
+    if doc.is_empty() {
+        quote!()
+    } else {
+        quote!(document.push_comment(#doc);)
+    }
+}
+
+/// Get `T` from `Wrapper<T>`, e.g. `Option<T>`
+fn generic_inner(ty: &Type, wrapper: &str) -> Option<Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner.clone()),
+        _ => None,
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "ConfigBuilder can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "ConfigBuilder can only be derived for structs with named fields",
+        ));
+    };
+    let attrs = parse_struct_attrs(input)?;
+    let fields = named
+        .named
+        .iter()
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let section_names = section_names(&fields);
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let builder = &attrs.builder;
+    let error = &attrs.error;
+    let builder_fields = builder_fields(&fields);
+    let write_config = gen_write_config(&fields, &section_names);
+    let sections = gen_sections(&fields, &section_names);
+    let section_keys = gen_section_keys(&fields, &section_names);
+    let parse_value = gen_parse_value(&fields, error);
+    let build = gen_build(name, &fields);
+    let indexed_methods = gen_indexed_methods(&fields, error);
+    let duplicates = attrs.duplicates.as_ref().map(|variant| {
+        quote! {
+            fn duplicates() -> ::parser::Duplicates {
+                ::parser::Duplicates::#variant
+            }
+        }
+    });
+    let schema = gen_schema(&input.attrs, &fields, &section_names);
+
+    let builder_doc = format!("Builder for [`{name}`], generated by `#[derive(ConfigBuilder)]`");
+    Ok(quote! {
+        #[doc = #builder_doc]
+        #[derive(Default)]
+        #vis struct #builder {
+            #(#builder_fields,)*
+        }
+
+        impl ::parser::Configurable for #name {
+            type Builder = #builder;
+        }
+
+        impl ::parser::ToConfig for #name {
+            #write_config
+        }
+
+        impl ::parser::ConfigBuilder for #builder {
+            type Output = #name;
+            type Error = #error;
+
+            #sections
+
+            #section_keys
+
+            #parse_value
+
+            #build
+
+            #indexed_methods
+
+            #duplicates
+
+            #schema
+        }
+    })
+}
+
+/// Sections of `fields` in order of first use
+fn section_names(fields: &[ConfigField]) -> Vec<&str> {
+    let mut section_names: Vec<&str> = vec![];
+    for field in fields {
+        if let Some(section) = field.section()
+            && !section_names.contains(&section)
+        {
+            section_names.push(section);
+        }
+    }
+    section_names
+}
+
+/// Type whose builder reads the keys of a nested or repeated `section`
+fn section_type<'a>(fields: &'a [ConfigField], section: &str) -> Option<&'a Type> {
+    fields.iter().find_map(|field| match &field.kind {
+        FieldKind::Nested { section: sec } if sec == section => Some(&field.ty),
+        FieldKind::Repeated {
+            section: sec,
+            element,
+        } if sec == section => Some(element.as_ref()),
+        _ => None,
+    })
+}
+
+/// Whether `section` is read by a repeated field
+fn is_repeated(fields: &[ConfigField], section: &str) -> bool {
+    fields.iter().any(
+        |field| matches!(&field.kind, FieldKind::Repeated { section: sec, .. } if sec == section),
+    )
+}
+
+/// Sections in the order they are written, the empty section has to come before any header
+fn write_order<'a>(section_names: &[&'a str]) -> Vec<&'a str> {
+    let mut write_sections = section_names.to_vec();
+    write_sections.sort_by_key(|section| !section.is_empty());
+    write_sections
+}
+
+/// Fields of the builder struct
+fn builder_fields(fields: &[ConfigField]) -> impl Iterator<Item = TokenStream2> + '_ {
+    fields.iter().filter_map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        match &field.kind {
+            FieldKind::Value(value) => {
+                let value_ty = &value.value_ty;
+                Some(quote!(#ident: ::core::option::Option<#value_ty>))
+            }
+            FieldKind::Nested { .. } => {
+                Some(quote!(#ident: <#ty as ::parser::Configurable>::Builder))
+            }
+            FieldKind::Repeated { element, .. } => {
+                Some(quote!(#ident: ::std::vec::Vec<<#element as ::parser::Configurable>::Builder>))
+            }
+            FieldKind::Skip => None,
+        }
+    })
+}
+
+/// `ConfigBuilder::sections`
+fn gen_sections(fields: &[ConfigField], section_names: &[&str]) -> TokenStream2 {
+    let sections = section_names.iter().map(|section| {
+        if is_repeated(fields, section) {
+            return quote!(::parser::Section::repeated(#section));
+        }
+        section_type(fields, section).map_or_else(
+            || {
+                let optional = fields.iter().all(|field| match &field.kind {
+                    FieldKind::Value(value) if value.section == *section => value.is_optional(),
+                    _ => true,
+                });
+                quote!(::parser::Section::new(#section, #optional))
+            },
+            |ty| {
+                quote!(::parser::Section::new(
+                    #section,
+                    <<#ty as ::parser::Configurable>::Builder as ::parser::ConfigBuilder>::sections()
+                        .iter()
+                        .all(|sec| !sec.name().is_empty() || sec.is_optional()),
+                ))
+            },
+        )
+    });
+    quote! {
+        fn sections() -> ::std::vec::Vec<::parser::Section> {
+            vec![#(#sections),*]
+        }
+    }
+}
+
+/// `ConfigBuilder::section_keys`
+fn gen_section_keys(fields: &[ConfigField], section_names: &[&str]) -> TokenStream2 {
+    let arms = section_names.iter().map(|section| {
+        if let Some(ty) = section_type(fields, section) {
+            return quote!(#section =>
+                <<#ty as ::parser::Configurable>::Builder as ::parser::ConfigBuilder>::section_keys("")
+                    .map_err(::core::convert::Into::into),
+            );
+        }
+        let keys = fields.iter().filter_map(|field| match &field.kind {
+            FieldKind::Value(value) if value.section == *section => {
+                let key = &value.key;
+                let optional = value.is_optional();
+                Some(quote!(::parser::Key::new(#key, #optional)))
+            }
+            _ => None,
+        });
+        quote!(#section => ::core::result::Result::Ok(vec![#(#keys),*]),)
+    });
+    quote! {
+        fn section_keys(
+            section: &str,
+        ) -> ::core::result::Result<::std::vec::Vec<::parser::Key>, Self::Error> {
+            match section {
+                #(#arms)*
+                _ => ::core::result::Result::Err(::parser::Error::unknown_section(section).into()),
+            }
+        }
+    }
+}
+
+/// `ConfigBuilder::parse_value`
+fn gen_parse_value(fields: &[ConfigField], error: &Path) -> TokenStream2 {
+    let arms = fields.iter().filter_map(|field| {
+        let ident = &field.ident;
+        match &field.kind {
+            FieldKind::Value(value) => {
+                let ValueField {
+                    section,
+                    key,
+                    parser,
+                    value_ty,
+                    ..
+                } = value.as_ref();
+                let parse = parser.as_ref().map_or_else(
+                    || quote!(<#value_ty as ::parser::FromConfigValue>::from_config_value(value)),
+                    |parser| quote!(#parser(value)),
+                );
+                Some(quote!((#section, #key) => {
+                    self.#ident = ::core::option::Option::Some(
+                        #parse.map_err(::core::convert::Into::<#error>::into)?,
+                    );
+                }))
+            }
+            FieldKind::Nested { section } => Some(quote!((#section, key) => {
+                ::parser::ConfigBuilder::parse_value(&mut self.#ident, "", key, value)
+                    .map_err(::core::convert::Into::<#error>::into)?;
+            })),
+            FieldKind::Repeated { .. } | FieldKind::Skip => None,
+        }
+    });
+    quote! {
+        fn parse_value(
+            &mut self,
+            section: &str,
+            key: &str,
+            value: &str,
+        ) -> ::core::result::Result<(), Self::Error> {
+            match (section, key) {
+                #(#arms)*
+                _ => {
+                    return ::core::result::Result::Err(
+                        ::parser::Error::unknown_key(section, key).into(),
+                    );
+                }
+            }
+            ::core::result::Result::Ok(())
+        }
+    }
+}
+
+/// `ConfigBuilder::start_indexed` and `ConfigBuilder::parse_indexed`, if there are repeated fields
+fn gen_indexed_methods(fields: &[ConfigField], error: &Path) -> TokenStream2 {
+    let repeated_fields: Vec<(&Ident, &String)> = fields
+        .iter()
+        .filter_map(|field| match &field.kind {
+            FieldKind::Repeated { section, .. } => Some((&field.ident, section)),
+            _ => None,
+        })
+        .collect();
+    if repeated_fields.is_empty() {
+        return quote!();
+    }
+    let start_arms = repeated_fields.iter().map(|(ident, section)| {
+        quote!(#section => {
+            if self.#ident.len() <= index {
+                self.#ident.resize_with(index + 1, ::core::default::Default::default);
+            }
+        })
+    });
+    let parse_arms = repeated_fields.iter().map(|(ident, section)| {
+        quote!(#section => {
+            let element = self.#ident.get_mut(index).ok_or_else(|| {
+                ::core::convert::Into::<#error>::into(::parser::Error::unknown_section(section))
+            })?;
+            ::parser::ConfigBuilder::parse_value(element, "", key, value)
+                .map_err(::core::convert::Into::<#error>::into)?;
+        })
+    });
+    quote! {
+        fn start_indexed(
+            &mut self,
+            section: &str,
+            index: usize,
+        ) -> ::core::result::Result<(), Self::Error> {
+            match section {
+                #(#start_arms)*
+                _ => {
+                    return ::core::result::Result::Err(
+                        ::parser::Error::unknown_section(section).into(),
+                    );
+                }
+            }
+            ::core::result::Result::Ok(())
+        }
+
+        fn parse_indexed(
+            &mut self,
+            section: &str,
+            index: usize,
+            key: &str,
+            value: &str,
+        ) -> ::core::result::Result<(), Self::Error> {
+            match section {
+                #(#parse_arms)*
+                _ => {
+                    return ::core::result::Result::Err(
+                        ::parser::Error::unknown_section(section).into(),
+                    );
+                }
+            }
+            ::core::result::Result::Ok(())
+        }
+    }
+}
+
+/// `ConfigBuilder::build`
+fn gen_build(name: &Ident, fields: &[ConfigField]) -> TokenStream2 {
+    let build_fields = fields.iter().map(|field| {
+        let ident = &field.ident;
+        match &field.kind {
+            FieldKind::Value(value) => match &value.default {
+                Some(default) => quote!(#ident: self.#ident.unwrap_or_else(|| #default)),
+                None if value.optional => quote!(#ident: self.#ident),
+                None => {
+                    let msg = format!("required key {} is missing", value.key);
+                    quote!(#ident: self.#ident.expect(#msg))
+                }
+            },
+            FieldKind::Nested { .. } => {
+                quote!(#ident: ::parser::ConfigBuilder::build(self.#ident))
+            }
+            FieldKind::Repeated { .. } => quote!(#ident: self
+                .#ident
+                .into_iter()
+                .map(::parser::ConfigBuilder::build)
+                .collect()),
+            FieldKind::Skip => quote!(#ident: ::core::default::Default::default()),
+        }
+    });
+    quote! {
+        fn build(self) -> Self::Output {
+            #name {
+                #(#build_fields,)*
+            }
+        }
+    }
+}
+
+/// `ToConfig::write_config`
+fn gen_write_config(fields: &[ConfigField], section_names: &[&str]) -> TokenStream2 {
+    let write_blocks = write_order(section_names).into_iter().map(|section| {
+        let header = fields.iter().find_map(|field| match &field.kind {
+            FieldKind::Nested { section: sec } if sec == section => Some(field.doc.as_str()),
+            _ => None,
+        });
+        let header = if section.is_empty() || is_repeated(fields, section) {
+            quote!()
+        } else {
+            let comment = write_comment(header.unwrap_or_default());
+            quote! {
+                if !document.is_empty() {
+                    document.push_blank();
+                }
+                #comment
+                document.push_section(#section);
+            }
+        };
+        let values = fields
+            .iter()
+            .filter_map(|field| write_field(field, section));
+        quote! {
+            #header
+            #(#values)*
+        }
+    });
+    quote! {
+        fn write_config(&self, document: &mut ::parser::ConfigDocument) {
+            #(#write_blocks)*
+        }
+    }
+}
+
+/// Write `field` if it belongs to `section`
+fn write_field(field: &ConfigField, section: &str) -> Option<TokenStream2> {
+    let ident = &field.ident;
+    let comment = write_comment(&field.doc);
+    match &field.kind {
+        FieldKind::Value(value) if value.section == section => {
+            let key = &value.key;
+            let write = if value.optional && value.default.is_none() {
+                quote! {
+                    match &self.#ident {
+                        ::core::option::Option::Some(value) => document.push_value(
+                            #key,
+                            &::parser::ToConfigValue::to_config_value(value),
+                        ),
+                        ::core::option::Option::None => {
+                            document.push_comment(&::std::format!("{} =", #key));
+                        }
+                    }
+                }
+            } else {
+                quote!(document.push_value(
+                    #key,
+                    &::parser::ToConfigValue::to_config_value(&self.#ident),
+                );)
+            };
+            Some(quote! {
+                if !document.is_empty() {
+                    document.push_blank();
+                }
+                #comment
+                #write
+            })
+        }
+        FieldKind::Nested { section: sec } if sec == section => {
+            Some(quote!(::parser::ToConfig::write_config(&self.#ident, document);))
+        }
+        FieldKind::Repeated { section: sec, .. } if sec == section => Some(quote! {
+            for element in &self.#ident {
+                if !document.is_empty() {
+                    document.push_blank();
+                }
+                #comment
+                document.push_repeated_section(#section);
+                ::parser::ToConfig::write_config(element, document);
+            }
+        }),
+        _ => None,
+    }
+}
+
+/// `ConfigBuilder::schema`, `attrs` are the attributes of the struct
+fn gen_schema(attrs: &[Attribute], fields: &[ConfigField], section_names: &[&str]) -> TokenStream2 {
+    let schema_sections = write_order(section_names).into_iter().map(|section| {
+        let nested = fields.iter().find(|field| {
+            matches!(
+                &field.kind,
+                FieldKind::Nested { section: sec } | FieldKind::Repeated { section: sec, .. }
+                    if sec == section
+            )
+        });
+        if let (Some(field), Some(ty)) = (nested, section_type(fields, section)) {
+            let doc = &field.doc;
+            let repeated = is_repeated(fields, section);
+            return quote! {{
+                let mut section = <<#ty as ::parser::Configurable>::Builder as ::parser::ConfigBuilder>::schema()
+                    .sections
+                    .into_iter()
+                    .find(|sec| sec.name.is_empty())
+                    .unwrap_or_default();
+                section.name = ::std::string::ToString::to_string(#section);
+                section.optional = optional(#section);
+                section.repeated = #repeated;
+                section.description = ::std::string::ToString::to_string(#doc);
+                section
+            }};
+        }
+        let description = if section.is_empty() {
+            doc_comment(attrs)
+        } else {
+            String::new()
+        };
+        let keys = fields.iter().filter_map(|field| key_schema(field, section));
+        quote!(::parser::SectionSchema {
+            name: ::std::string::ToString::to_string(#section),
+            optional: optional(#section),
+            repeated: false,
+            description: ::std::string::ToString::to_string(#description),
+            keys: vec![#(#keys),*],
+        })
+    });
+    quote! {
+        fn schema() -> ::parser::Schema {
+            let sections = <Self as ::parser::ConfigBuilder>::sections();
+            let optional = |name: &str| {
+                sections
+                    .iter()
+                    .any(|sec| sec.name() == name && sec.is_optional())
+            };
+            ::parser::Schema {
+                sections: vec![#(#schema_sections),*],
+            }
+        }
+    }
+}
+
+/// Schema of the key of `field`, if it is read from a key in `section`
+fn key_schema(field: &ConfigField, section: &str) -> Option<TokenStream2> {
+    let FieldKind::Value(value) = &field.kind else {
+        return None;
+    };
+    let ValueField {
+        section: sec,
+        key,
+        default,
+        parser,
+        value_ty,
+        ..
+    } = value.as_ref();
+    if sec != section {
+        return None;
+    }
+    let value_type = if parser.is_some() {
+        let ty = quote!(#value_ty).to_string();
+        quote!(::std::string::ToString::to_string(#ty))
+    } else {
+        quote!(<#value_ty as ::parser::FromConfigValue>::expected())
+    };
+    let default = default.as_ref().map_or_else(
+        || quote!(::core::option::Option::None),
+        |default| {
+            quote!(::core::option::Option::Some({
+                let default: #value_ty = #default;
+                ::parser::ToConfigValue::to_config_value(&default)
+            }))
+        },
+    );
+    let optional = value.is_optional();
+    let doc = &field.doc;
+    Some(quote!(::parser::KeySchema {
+        name: ::std::string::ToString::to_string(#key),
+        value_type: #value_type,
+        default: #default,
+        optional: #optional,
+        description: ::std::string::ToString::to_string(#doc),
+    }))
+}
+
+/// Convert `CamelCase` to `kebab-case`
+fn kebab_case(name: &str) -> String {
+    let mut kebab = String::with_capacity(name.len());
+    for (ind, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() && ind != 0 {
+            kebab.push('-');
+        }
+        kebab.extend(ch.to_lowercase());
+    }
+    kebab
+}
+
+/// Variants of an enum without fields, together with their name in config files
+fn enum_variants(input: &DeriveInput) -> syn::Result<Vec<(&Ident, String)>> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "config values can only be derived for enums",
+        ));
+    };
+    let mut variants = vec![];
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.span(),
+                "config values can only be derived for variants without fields",
+            ));
+        }
+        let mut value_name = kebab_case(&variant.ident.to_string());
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("config"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    value_name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown config attribute"))
+                }
+            })?;
+        }
+        variants.push((&variant.ident, value_name));
+    }
+    Ok(variants)
+}
+
+fn expand_enum(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let variants = enum_variants(input)?;
+    let name = &input.ident;
+    let expected = format!(
+        "one of {}",
+        variants
+            .iter()
+            .map(|(_, value_name)| value_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let arms = variants.iter().map(|(ident, value_name)| {
+        quote!(value if value.eq_ignore_ascii_case(#value_name) => ::core::result::Result::Ok(Self::#ident),)
+    });
+    Ok(quote! {
+        impl ::parser::FromConfigValue for #name {
+            fn expected() -> ::std::string::String {
+                ::std::string::ToString::to_string(#expected)
+            }
+
+            fn from_config_value(value: &str) -> ::core::result::Result<Self, ::parser::Error> {
+                match value {
+                    #(#arms)*
+                    _ => ::core::result::Result::Err(::parser::Error::invalid_type(
+                        value,
+                        &<Self as ::parser::FromConfigValue>::expected(),
+                    )),
+                }
+            }
+        }
+    })
+}
+
+fn expand_to_value(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let variants = enum_variants(input)?;
+    let name = &input.ident;
+    let arms = variants
+        .iter()
+        .map(|(ident, value_name)| quote!(Self::#ident => #value_name,));
+    Ok(quote! {
+        impl ::parser::ToConfigValue for #name {
+            fn to_config_value(&self) -> ::std::string::String {
+                let value = match self {
+                    #(#arms)*
+                };
+                ::std::string::ToString::to_string(value)
+            }
+        }
+    })
+}